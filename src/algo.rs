@@ -1,15 +1,19 @@
 pub mod algo {
     use crate::graph::graph::*;
+    use std::cmp::Ordering;
+    use std::collections::BinaryHeap;
     use std::collections::HashMap;
     use std::collections::HashSet;
-    use std::f64::INFINITY;
+    use std::collections::VecDeque;
     extern crate rayon;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
     use rayon::prelude::*;
 
     fn min_dist_node(queue: &HashSet<Node>, dist: &HashMap<String, f64>) -> String {
         // minimum distance node
         queue.iter().fold("".to_string(), |mut min_node, node| {
-            let mut current_min_dist = INFINITY;
+            let mut current_min_dist = f64::INFINITY;
             if dist.contains_key(&min_node) {
                 current_min_dist = dist[&min_node];
             }
@@ -20,7 +24,164 @@ pub mod algo {
         })
     }
 
+    #[derive(PartialEq)]
+    struct HeapEntry(f64, usize);
+
+    impl Eq for HeapEntry {}
+
+    // reversed so that BinaryHeap pops the smallest distance first
+    impl Ord for HeapEntry {
+        fn cmp(&self, other: &Self) -> Ordering {
+            other
+                .0
+                .partial_cmp(&self.0)
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| other.1.cmp(&self.1))
+        }
+    }
+
+    impl PartialOrd for HeapEntry {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    // node index => [(neighbor index, link weight)], links are followed both
+    // ways when the graph is undirected
+    fn index_adjacency(graph: &Graph) -> Vec<Vec<(usize, f64)>> {
+        graph
+            .links
+            .values()
+            .fold(vec![Vec::new(); graph.nodes.len()], |mut adj, l| {
+                let (s, t) = (graph.nodes_map[&l.source], graph.nodes_map[&l.target]);
+                adj[s].push((t, l.weight));
+                if !graph.directed && s != t {
+                    adj[t].push((s, l.weight));
+                }
+                adj
+            })
+    }
+
+    // single source dijkstra over the index adjacency, every edge costs 1.0
+    // when use_weights is false and edges for which `blocked(u, v)` is true
+    // are skipped. returns (distances, previous node on the shortest path),
+    // unreachable nodes stay at INFINITY with no previous node
+    fn dijkstra_tree<F: Fn(usize, usize) -> bool>(
+        adj: &[Vec<(usize, f64)>],
+        source: usize,
+        use_weights: bool,
+        blocked: F,
+    ) -> (Vec<f64>, Vec<Option<usize>>) {
+        let mut dist = vec![f64::INFINITY; adj.len()];
+        let mut prev = vec![None; adj.len()];
+        let mut heap = BinaryHeap::new();
+        dist[source] = 0.0;
+        heap.push(HeapEntry(0.0, source));
+        while let Some(HeapEntry(d, u)) = heap.pop() {
+            if d > dist[u] {
+                continue;
+            }
+            adj[u].iter().for_each(|&(v, w)| {
+                if blocked(u, v) {
+                    return;
+                }
+                let alt = d + if use_weights { w } else { 1.0 };
+                if alt < dist[v] {
+                    dist[v] = alt;
+                    prev[v] = Some(u);
+                    heap.push(HeapEntry(alt, v));
+                }
+            });
+        }
+        (dist, prev)
+    }
+
+    fn distances_from(adj: &[Vec<(usize, f64)>], source: usize, use_weights: bool) -> Vec<f64> {
+        dijkstra_tree(adj, source, use_weights, |_, _| false).0
+    }
+
+    // walks the previous node links back from target, empty when the target
+    // was not reached
+    fn trace_path(prev: &[Option<usize>], source: usize, target: usize) -> Vec<usize> {
+        let mut path = vec![target];
+        let mut c = target;
+        while c != source {
+            match prev[c] {
+                Some(p) => {
+                    path.push(p);
+                    c = p;
+                }
+                None => return Vec::new(),
+            }
+        }
+        path.reverse();
+        path
+    }
+
+    // node index => neighbor indices with every link followed both ways
+    // whatever the `directed` flag says, self loops are left out
+    fn undirected_adjacency(graph: &Graph) -> Vec<Vec<usize>> {
+        let mut adj = vec![Vec::new(); graph.nodes.len()];
+        graph.links.values().for_each(|l| {
+            let (s, t) = (graph.nodes_map[&l.source], graph.nodes_map[&l.target]);
+            if s != t {
+                adj[s].push(t);
+                adj[t].push(s);
+            }
+        });
+        adj
+    }
+
+    // weakly connected components as lists of node indices, ordered by the
+    // index of their first node
+    fn weak_components(graph: &Graph) -> Vec<Vec<usize>> {
+        let adj = undirected_adjacency(graph);
+        let mut seen = vec![false; graph.nodes.len()];
+        let mut components = Vec::new();
+        for root in 0..graph.nodes.len() {
+            if seen[root] {
+                continue;
+            }
+            seen[root] = true;
+            let mut component = vec![root];
+            let mut i = 0;
+            while i < component.len() {
+                adj[component[i]].iter().for_each(|&v| {
+                    if !seen[v] {
+                        seen[v] = true;
+                        component.push(v);
+                    }
+                });
+                i += 1;
+            }
+            components.push(component);
+        }
+        components
+    }
+
+    // the biggest weakly connected component, the first one wins on ties
+    fn largest_component(graph: &Graph) -> Vec<usize> {
+        weak_components(graph)
+            .into_iter()
+            .fold(
+                Vec::new(),
+                |best, c| if c.len() > best.len() { c } else { best },
+            )
+    }
+
     pub fn dijkstra_shortest(graph: &Graph, start: &str, end: &str) -> Vec<Node> {
+        dijkstra_shortest_weighted(graph, start, end, graph.weighted)
+    }
+
+    // like dijkstra_shortest, but whether link weights are used is decided by
+    // the caller instead of the graph's `weighted` flag, hops count 1.0 when
+    // use_weights is false
+    pub fn dijkstra_shortest_weighted(
+        graph: &Graph,
+        start: &str,
+        end: &str,
+        use_weights: bool,
+    ) -> Vec<Node> {
         let mut result: Vec<Node> = Vec::new();
         // if one of start and end is not in the graph, return empty vector
         if !graph.nodes_map.contains_key(start) || !graph.nodes_map.contains_key(end) {
@@ -35,14 +196,14 @@ pub mod algo {
                 if n.id == start {
                     (n.clone(), (n.id.clone(), 0.0))
                 } else {
-                    (n.clone(), (n.id.clone(), INFINITY))
+                    (n.clone(), (n.id.clone(), f64::INFINITY))
                 }
             })
             .unzip();
         // node id => previous node id
         let mut prev: HashMap<String, String> = HashMap::new();
 
-        while queue.len() > 0 {
+        while !queue.is_empty() {
             // minimum distance node
             let min_node = min_dist_node(&queue, &dist);
             if min_node == end {
@@ -56,9 +217,9 @@ pub mod algo {
                 break;
             }
             graph.direct_connected(&min_node).iter().for_each(|n| {
-                if queue.contains(&n) {
+                if queue.contains(n) {
                     let mut alt = dist[&min_node];
-                    if graph.weighted {
+                    if use_weights {
                         alt += graph.get_link(&min_node, &n.id).unwrap().weight;
                     } else {
                         alt += 1.0
@@ -81,7 +242,7 @@ pub mod algo {
             }
             // This unwrap is safe because all t is from prev,
             // which must be in the graph
-            result.insert(0, graph.get_node(&c).unwrap().clone());
+            result.insert(0, graph.get_node(c).unwrap());
             if let Some(p) = prev.get(c) {
                 // trace back to start
                 if c == start {
@@ -96,10 +257,1383 @@ pub mod algo {
         result
     }
 
+    // cost of a path returned by the path functions in this module, see
+    // `Graph::path_cost`: link weights on weighted graphs, hops otherwise,
+    // None when a consecutive pair is not linked
+    pub fn total_path_weight(graph: &Graph, path: &[Node]) -> Option<f64> {
+        graph.path_cost(path)
+    }
+
+    // sum of shortest path distances over every pair of nodes in the largest
+    // connected component, unordered pairs for undirected graphs and ordered
+    // reachable pairs for directed graphs. returns (sum, number of pairs)
+    fn path_length_sum(graph: &Graph) -> (f64, usize) {
+        let component = largest_component(graph);
+        let adj = index_adjacency(graph);
+        let (sum, pairs) = component
+            .par_iter()
+            .map(|&s| {
+                let dist = distances_from(&adj, s, graph.weighted);
+                component
+                    .iter()
+                    .filter(|&&t| t != s && dist[t].is_finite())
+                    .fold((0.0, 0), |(sum, pairs), &t| (sum + dist[t], pairs + 1))
+            })
+            .reduce(|| (0.0, 0), |a, b| (a.0 + b.0, a.1 + b.1));
+        if graph.directed {
+            (sum, pairs)
+        } else {
+            // every unordered pair was counted from both ends
+            (sum / 2.0, pairs / 2)
+        }
+    }
+
+    // sum of shortest path distances over all node pairs of the largest
+    // connected component, weights are used when the graph is weighted
+    pub fn wiener_index(graph: &Graph) -> f64 {
+        path_length_sum(graph).0
+    }
+
+    // wiener index divided by the number of node pairs, 0.0 when there are
+    // no pairs. for directed graphs only reachable pairs are counted
+    pub fn average_path_length(graph: &Graph) -> f64 {
+        let (sum, pairs) = path_length_sum(graph);
+        if pairs == 0 {
+            0.0
+        } else {
+            sum / pairs as f64
+        }
+    }
+
+    // bellman-ford from a virtual source linked to every node with weight 0,
+    // so cycles anywhere in the graph are found. returns the distance of
+    // every node from the virtual source, or None on a negative cycle
+    fn bellman_ford_potential(adj: &[Vec<(usize, f64)>]) -> Option<Vec<f64>> {
+        let mut dist = vec![0.0; adj.len()];
+        // n rounds settle all shortest paths from the virtual source, the
+        // extra round only improves when a negative cycle exists
+        for _ in 0..=adj.len() {
+            let mut changed = false;
+            (0..adj.len()).for_each(|u| {
+                adj[u].iter().for_each(|&(v, w)| {
+                    if dist[u] + w < dist[v] {
+                        dist[v] = dist[u] + w;
+                        changed = true;
+                    }
+                });
+            });
+            if !changed {
+                return Some(dist);
+            }
+        }
+        None
+    }
+
+    // link weights are always used regardless of the weighted flag, an
+    // undirected negative link counts as a negative cycle since it can be
+    // walked back and forth
+    pub fn has_negative_cycle(graph: &Graph) -> bool {
+        bellman_ford_potential(&index_adjacency(graph)).is_none()
+    }
+
+    // checks the degree and connectivity conditions for an eulerian trail,
+    // returns the index of the node the trail has to start from and whether
+    // the trail is a circuit. graphs without links have no trail
+    fn eulerian_start(graph: &Graph) -> Option<(usize, bool)> {
+        if graph.links.is_empty() {
+            return None;
+        }
+        let n = graph.nodes.len();
+        let (mut outdeg, mut indeg) = (vec![0i64; n], vec![0i64; n]);
+        graph.links.values().for_each(|l| {
+            outdeg[graph.nodes_map[&l.source]] += 1;
+            indeg[graph.nodes_map[&l.target]] += 1;
+        });
+        // all links have to live in a single component
+        let linked = weak_components(graph)
+            .iter()
+            .filter(|c| c.iter().any(|&i| outdeg[i] + indeg[i] > 0))
+            .count();
+        if linked != 1 {
+            return None;
+        }
+        let first_linked = (0..n).find(|&i| outdeg[i] + indeg[i] > 0)?;
+        if graph.directed {
+            let (mut start, mut ends) = (None, 0);
+            for i in 0..n {
+                match outdeg[i] - indeg[i] {
+                    0 => {}
+                    1 if start.is_none() => start = Some(i),
+                    -1 if ends == 0 => ends += 1,
+                    _ => return None,
+                }
+            }
+            match (start, ends) {
+                (None, 0) => Some((first_linked, true)),
+                (Some(s), 1) => Some((s, false)),
+                _ => None,
+            }
+        } else {
+            let odd: Vec<usize> = (0..n)
+                .filter(|&i| (outdeg[i] + indeg[i]) % 2 == 1)
+                .collect();
+            match odd.len() {
+                0 => Some((first_linked, true)),
+                2 => Some((odd[0], false)),
+                _ => None,
+            }
+        }
+    }
+
+    // a closed trail using every link exactly once exists
+    pub fn has_eulerian_circuit(graph: &Graph) -> bool {
+        matches!(eulerian_start(graph), Some((_, true)))
+    }
+
+    // a trail using every link exactly once exists, a circuit also counts
+    pub fn has_eulerian_path(graph: &Graph) -> bool {
+        eulerian_start(graph).is_some()
+    }
+
+    // hierholzer's algorithm, returns the visited nodes in order (a circuit
+    // starts and ends with the same node) or an empty vector when there is no
+    // eulerian path
+    pub fn eulerian_path(graph: &Graph) -> Vec<Node> {
+        let start = match eulerian_start(graph) {
+            Some((start, _)) => start,
+            None => return Vec::new(),
+        };
+        let mut keys: Vec<&String> = graph.links.keys().collect();
+        keys.sort();
+        // node index => [(neighbor index, link id)]
+        let mut adj: Vec<Vec<(usize, usize)>> = vec![Vec::new(); graph.nodes.len()];
+        keys.iter().enumerate().for_each(|(id, k)| {
+            let l = &graph.links[*k];
+            let (s, t) = (graph.nodes_map[&l.source], graph.nodes_map[&l.target]);
+            adj[s].push((t, id));
+            if !graph.directed && s != t {
+                adj[t].push((s, id));
+            }
+        });
+        let mut used = vec![false; keys.len()];
+        let mut next = vec![0; graph.nodes.len()];
+        let mut stack = vec![start];
+        let mut path = Vec::new();
+        while let Some(&u) = stack.last() {
+            while next[u] < adj[u].len() && used[adj[u][next[u]].1] {
+                next[u] += 1;
+            }
+            if next[u] < adj[u].len() {
+                let (v, id) = adj[u][next[u]];
+                used[id] = true;
+                stack.push(v);
+            } else {
+                path.push(graph.nodes[u].clone());
+                stack.pop();
+            }
+        }
+        path.reverse();
+        path
+    }
+
+    // breadth first search that stops as soon as `end` is found, a node is
+    // always reachable from itself
+    pub fn is_reachable(graph: &Graph, start: &str, end: &str) -> bool {
+        let (s, e) = match (graph.node_index(start), graph.node_index(end)) {
+            (Some(s), Some(e)) => (s, e),
+            _ => return false,
+        };
+        if s == e {
+            return true;
+        }
+        let adj = index_adjacency(graph);
+        let mut seen = vec![false; adj.len()];
+        let mut queue = VecDeque::new();
+        seen[s] = true;
+        queue.push_back(s);
+        while let Some(u) = queue.pop_front() {
+            for &(v, _) in &adj[u] {
+                if v == e {
+                    return true;
+                }
+                if !seen[v] {
+                    seen[v] = true;
+                    queue.push_back(v);
+                }
+            }
+        }
+        false
+    }
+
+    // whether a path leads from start to end, same as is_reachable
+    pub fn contains_path(graph: &Graph, start: &str, end: &str) -> bool {
+        is_reachable(graph, start, end)
+    }
+
+    // number of links on the shortest path from start to end ignoring the
+    // weights, Some(0) when start == end and None when end is unreachable
+    pub fn path_length(graph: &Graph, start: &str, end: &str) -> Option<usize> {
+        let (s, e) = (graph.node_index(start)?, graph.node_index(end)?);
+        let hops = distances_from(&index_adjacency(graph), s, false)[e];
+        if hops.is_finite() {
+            Some(hops as usize)
+        } else {
+            None
+        }
+    }
+
+    // marks the nodes reachable from node index s by breadth first search,
+    // s itself included
+    fn reachable_marks(graph: &Graph, s: usize) -> Vec<bool> {
+        let adj = index_adjacency(graph);
+        let mut seen = vec![false; adj.len()];
+        let mut queue = VecDeque::new();
+        seen[s] = true;
+        queue.push_back(s);
+        while let Some(u) = queue.pop_front() {
+            for &(v, _) in &adj[u] {
+                if !seen[v] {
+                    seen[v] = true;
+                    queue.push_back(v);
+                }
+            }
+        }
+        seen
+    }
+
+    // ids of every node reachable from start by breadth first search along
+    // the link directions (both ways when undirected). start itself is
+    // included as it is reachable in zero steps, unknown ids give an empty set
+    pub fn reachable_set(graph: &Graph, start: &str) -> HashSet<String> {
+        match graph.node_index(start) {
+            Some(s) => reachable_marks(graph, s)
+                .iter()
+                .enumerate()
+                .filter(|(_, &reached)| reached)
+                .map(|(u, _)| graph.nodes[u].id.clone())
+                .collect(),
+            None => HashSet::new(),
+        }
+    }
+
+    // number of other nodes reachable from start, links are followed along
+    // their direction (both ways when undirected). 0 for unknown ids
+    pub fn reachable_count(graph: &Graph, start: &str) -> usize {
+        match graph.node_index(start) {
+            Some(s) => reachable_marks(graph, s).iter().filter(|&&r| r).count() - 1,
+            None => 0,
+        }
+    }
+
+    // all pairs shortest path distances, rows and columns follow the returned
+    // id ordering (the `nodes` order). unreachable pairs are INFINITY, link
+    // weights are used when the graph is weighted, otherwise hop counts
+    pub fn distance_matrix(graph: &Graph) -> (Vec<Vec<f64>>, Vec<String>) {
+        let adj = index_adjacency(graph);
+        let matrix = (0..adj.len())
+            .into_par_iter()
+            .map(|s| distances_from(&adj, s, graph.weighted))
+            .collect();
+        (matrix, graph.nodes.iter().map(|n| n.id.clone()).collect())
+    }
+
+    // immediate dominators from `entry` using the cooper-harvey-kennedy
+    // iterative algorithm. every node reachable from entry maps to its
+    // immediate dominator, the entry itself and unreachable nodes are omitted
+    pub fn dominator_tree(graph: &Graph, entry: &str) -> HashMap<String, String> {
+        let mut result = HashMap::new();
+        let root = match graph.node_index(entry) {
+            Some(root) => root,
+            None => return result,
+        };
+        let adj = index_adjacency(graph);
+        // iterative dfs to number reachable nodes in postorder
+        let mut postorder = vec![usize::MAX; adj.len()];
+        let mut order = Vec::new();
+        let mut visited = vec![false; adj.len()];
+        let mut stack = vec![(root, 0)];
+        visited[root] = true;
+        while let Some((u, i)) = stack.pop() {
+            if i < adj[u].len() {
+                stack.push((u, i + 1));
+                let v = adj[u][i].0;
+                if !visited[v] {
+                    visited[v] = true;
+                    stack.push((v, 0));
+                }
+            } else {
+                postorder[u] = order.len();
+                order.push(u);
+            }
+        }
+        let mut preds = vec![Vec::new(); adj.len()];
+        order.iter().for_each(|&u| {
+            adj[u].iter().for_each(|&(v, _)| preds[v].push(u));
+        });
+        let mut idom = vec![usize::MAX; adj.len()];
+        idom[root] = root;
+        let intersect = |idom: &[usize], mut a: usize, mut b: usize| {
+            while a != b {
+                while postorder[a] < postorder[b] {
+                    a = idom[a];
+                }
+                while postorder[b] < postorder[a] {
+                    b = idom[b];
+                }
+            }
+            a
+        };
+        let mut changed = true;
+        while changed {
+            changed = false;
+            // reverse postorder, skipping the entry
+            for &u in order.iter().rev().skip(1) {
+                let new_idom = preds[u].iter().filter(|&&p| idom[p] != usize::MAX).fold(
+                    usize::MAX,
+                    |acc, &p| {
+                        if acc == usize::MAX {
+                            p
+                        } else {
+                            intersect(&idom, p, acc)
+                        }
+                    },
+                );
+                if idom[u] != new_idom {
+                    idom[u] = new_idom;
+                    changed = true;
+                }
+            }
+        }
+        order.iter().filter(|&&u| u != root).for_each(|&u| {
+            result.insert(graph.nodes[u].id.clone(), graph.nodes[idom[u]].id.clone());
+        });
+        result
+    }
+
+    // the best simple path from start to end that is not the shortest one.
+    // any other simple path has to skip at least one link of the shortest
+    // path, so the answer is the best path found with one of those links
+    // removed at a time. returns an empty vector when there is no alternative
+    pub fn second_shortest_path(graph: &Graph, start: &str, end: &str) -> Vec<Node> {
+        let (s, e) = match (graph.node_index(start), graph.node_index(end)) {
+            (Some(s), Some(e)) if s != e => (s, e),
+            _ => return Vec::new(),
+        };
+        let adj = index_adjacency(graph);
+        let (_, prev) = dijkstra_tree(&adj, s, graph.weighted, |_, _| false);
+        let shortest = trace_path(&prev, s, e);
+        let best = shortest
+            .windows(2)
+            .filter_map(|pair| {
+                let (a, b) = (pair[0], pair[1]);
+                let (dist, prev) = dijkstra_tree(&adj, s, graph.weighted, |u, v| {
+                    (u == a && v == b) || (!graph.directed && u == b && v == a)
+                });
+                if dist[e].is_finite() {
+                    Some((dist[e], trace_path(&prev, s, e)))
+                } else {
+                    None
+                }
+            })
+            .fold(
+                None,
+                |best: Option<(f64, Vec<usize>)>, candidate| match best {
+                    Some(b) if b.0 <= candidate.0 => Some(b),
+                    _ => Some(candidate),
+                },
+            );
+        best.map(|(_, path)| path.iter().map(|&i| graph.nodes[i].clone()).collect())
+            .unwrap_or_default()
+    }
+
+    // dijkstra from start to end treating the blocked (source, target) links
+    // as absent, on undirected graphs a blocked pair closes the link both
+    // ways. unknown ids in `blocked` are ignored, returns an empty vector
+    // when end can not be reached
+    pub fn shortest_path_avoiding(
+        graph: &Graph,
+        start: &str,
+        end: &str,
+        blocked: &[(String, String)],
+    ) -> Vec<Node> {
+        let (s, e) = match (graph.node_index(start), graph.node_index(end)) {
+            (Some(s), Some(e)) => (s, e),
+            _ => return Vec::new(),
+        };
+        let closed: HashSet<(usize, usize)> = blocked
+            .iter()
+            .filter_map(|(a, b)| Some((graph.node_index(a)?, graph.node_index(b)?)))
+            .collect();
+        let (_, prev) = dijkstra_tree(&index_adjacency(graph), s, graph.weighted, |u, v| {
+            closed.contains(&(u, v)) || (!graph.directed && closed.contains(&(v, u)))
+        });
+        trace_path(&prev, s, e)
+            .iter()
+            .map(|&i| graph.nodes[i].clone())
+            .collect()
+    }
+
+    // one dijkstra run from start, then paths are traced back for every
+    // target. missing or unreachable targets map to an empty vector
+    pub fn shortest_paths_from(
+        graph: &Graph,
+        start: &str,
+        targets: &[String],
+    ) -> HashMap<String, Vec<Node>> {
+        let tree = graph.node_index(start).map(|s| {
+            let adj = index_adjacency(graph);
+            (s, dijkstra_tree(&adj, s, graph.weighted, |_, _| false).1)
+        });
+        targets
+            .iter()
+            .map(|t| {
+                let path = match (&tree, graph.node_index(t)) {
+                    (Some((s, prev)), Some(e)) => trace_path(prev, *s, e)
+                        .iter()
+                        .map(|&i| graph.nodes[i].clone())
+                        .collect(),
+                    _ => Vec::new(),
+                };
+                (t.clone(), path)
+            })
+            .collect()
+    }
+
+    // (node index, eccentricity) for every node of the largest connected
+    // component, the eccentricity is INFINITY when some node of the component
+    // can not be reached (directed graphs)
+    fn component_eccentricities(graph: &Graph) -> Vec<(usize, f64)> {
+        let mut component = largest_component(graph);
+        component.sort_unstable();
+        let adj = index_adjacency(graph);
+        component
+            .par_iter()
+            .map(|&s| {
+                let dist = distances_from(&adj, s, graph.weighted);
+                (
+                    s,
+                    component.iter().fold(0.0, |ecc: f64, &t| ecc.max(dist[t])),
+                )
+            })
+            .collect()
+    }
+
+    fn nodes_with_eccentricity(graph: &Graph, pick: fn(f64, f64) -> f64) -> Vec<Node> {
+        let ecc = component_eccentricities(graph);
+        let target = match ecc.iter().map(|&(_, e)| e).reduce(pick) {
+            Some(target) => target,
+            None => return Vec::new(),
+        };
+        ecc.iter()
+            .filter(|&&(_, e)| e == target)
+            .map(|&(i, _)| graph.nodes[i].clone())
+            .collect()
+    }
+
+    // nodes whose eccentricity equals the radius, over the largest component
+    pub fn center(graph: &Graph) -> Vec<Node> {
+        nodes_with_eccentricity(graph, f64::min)
+    }
+
+    // nodes whose eccentricity equals the diameter, over the largest component
+    pub fn periphery(graph: &Graph) -> Vec<Node> {
+        nodes_with_eccentricity(graph, f64::max)
+    }
+
+    // stoer-wagner global minimum cut over the undirected interpretation of
+    // the graph, link weights are used when the graph is weighted. returns
+    // the cut weight and the nodes on one side of the cut. a disconnected
+    // graph has a cut of 0.0, graphs with fewer than two nodes have no cut
+    // and return (0.0, []). runs in O(n^3) on a dense weight matrix
+    pub fn min_cut(graph: &Graph) -> (f64, Vec<Node>) {
+        let n = graph.nodes.len();
+        if n < 2 {
+            return (0.0, Vec::new());
+        }
+        let mut w = vec![vec![0.0; n]; n];
+        graph.links.values().for_each(|l| {
+            let (s, t) = (graph.nodes_map[&l.source], graph.nodes_map[&l.target]);
+            if s != t {
+                let weight = if graph.weighted { l.weight } else { 1.0 };
+                w[s][t] += weight;
+                w[t][s] += weight;
+            }
+        });
+        // original nodes merged into each remaining vertex
+        let mut groups: Vec<Vec<usize>> = (0..n).map(|i| vec![i]).collect();
+        let mut active: Vec<usize> = (0..n).collect();
+        let (mut best, mut best_side) = (f64::INFINITY, Vec::new());
+        while active.len() > 1 {
+            // maximum adjacency ordering, the last two vertices get merged
+            let mut connectivity = vec![0.0f64; n];
+            let mut added = vec![false; n];
+            let mut prev = active[0];
+            for i in 0..active.len() {
+                let v = *active
+                    .iter()
+                    .filter(|&&v| !added[v])
+                    .max_by(|&&a, &&b| connectivity[a].total_cmp(&connectivity[b]))
+                    .unwrap();
+                added[v] = true;
+                if i == active.len() - 1 {
+                    if connectivity[v] < best {
+                        best = connectivity[v];
+                        best_side = groups[v].clone();
+                    }
+                    let merged_row = w[v].clone();
+                    merged_row.iter().enumerate().for_each(|(u, &weight)| {
+                        w[prev][u] += weight;
+                        w[u][prev] = w[prev][u];
+                    });
+                    let merged = std::mem::take(&mut groups[v]);
+                    groups[prev].extend(merged);
+                    active.retain(|&u| u != v);
+                } else {
+                    active.iter().for_each(|&u| connectivity[u] += w[v][u]);
+                    prev = v;
+                }
+            }
+        }
+        best_side.sort_unstable();
+        (
+            best,
+            best_side.iter().map(|&i| graph.nodes[i].clone()).collect(),
+        )
+    }
+
+    // greedy maximal independent set over the undirected interpretation:
+    // nodes are taken in `nodes` order unless a neighbor was already taken.
+    // the set can not be extended but is not necessarily the largest one
+    pub fn maximal_independent_set(graph: &Graph) -> Vec<Node> {
+        let adj = undirected_adjacency(graph);
+        let mut blocked = vec![false; adj.len()];
+        let mut result = Vec::new();
+        for (u, neighbors) in adj.iter().enumerate() {
+            if blocked[u] {
+                continue;
+            }
+            result.push(graph.nodes[u].clone());
+            neighbors.iter().for_each(|&v| blocked[v] = true);
+        }
+        result
+    }
+
+    // 2-approximate minimum vertex cover: both ends of every link of a greedy
+    // maximal matching (links taken in key order). nodes are returned in
+    // `nodes` order
+    pub fn vertex_cover_approx(graph: &Graph) -> Vec<Node> {
+        let mut keys: Vec<&String> = graph.links.keys().collect();
+        keys.sort();
+        let mut covered = vec![false; graph.nodes.len()];
+        keys.iter().for_each(|k| {
+            let l = &graph.links[*k];
+            let (s, t) = (graph.nodes_map[&l.source], graph.nodes_map[&l.target]);
+            if !covered[s] && !covered[t] {
+                covered[s] = true;
+                covered[t] = true;
+            }
+        });
+        graph
+            .nodes
+            .iter()
+            .zip(covered)
+            .filter(|(_, c)| *c)
+            .map(|(n, _)| n.clone())
+            .collect()
+    }
+
+    // links of every biconnected component (maximal parts without an
+    // articulation point) over the undirected interpretation, found with the
+    // hopcroft-tarjan dfs edge stack. a bridge forms a component on its own,
+    // self loops belong to no component. links in a component are sorted by key
+    pub fn biconnected_components(graph: &Graph) -> Vec<Vec<Link>> {
+        let mut keys: Vec<&String> = graph.links.keys().collect();
+        keys.sort();
+        let n = graph.nodes.len();
+        // node index => [(neighbor index, link id)]
+        let mut adj: Vec<Vec<(usize, usize)>> = vec![Vec::new(); n];
+        keys.iter().enumerate().for_each(|(id, k)| {
+            let l = &graph.links[*k];
+            let (s, t) = (graph.nodes_map[&l.source], graph.nodes_map[&l.target]);
+            if s != t {
+                adj[s].push((t, id));
+                adj[t].push((s, id));
+            }
+        });
+        let mut disc = vec![usize::MAX; n];
+        let mut low = vec![0; n];
+        let mut time = 0;
+        let mut edges: Vec<usize> = Vec::new();
+        let mut components = Vec::new();
+        for root in 0..n {
+            if disc[root] != usize::MAX {
+                continue;
+            }
+            disc[root] = time;
+            low[root] = time;
+            time += 1;
+            // (node, link id used to reach it, next neighbor to look at)
+            let mut stack: Vec<(usize, Option<usize>, usize)> = vec![(root, None, 0)];
+            while let Some(&mut (u, parent_link, ref mut next)) = stack.last_mut() {
+                if *next < adj[u].len() {
+                    let (v, id) = adj[u][*next];
+                    *next += 1;
+                    if Some(id) == parent_link {
+                        continue;
+                    }
+                    if disc[v] == usize::MAX {
+                        edges.push(id);
+                        disc[v] = time;
+                        low[v] = time;
+                        time += 1;
+                        stack.push((v, Some(id), 0));
+                    } else if disc[v] < disc[u] {
+                        // back edge to an ancestor
+                        edges.push(id);
+                        low[u] = low[u].min(disc[v]);
+                    }
+                    continue;
+                }
+                stack.pop();
+                if let (Some(&(p, _, _)), Some(id)) = (stack.last(), parent_link) {
+                    low[p] = low[p].min(low[u]);
+                    if low[u] >= disc[p] {
+                        // p separates u's subtree, everything above the tree
+                        // link p-u on the stack is one component
+                        let at = edges.iter().rposition(|&e| e == id).unwrap();
+                        let mut component: Vec<Link> = edges
+                            .drain(at..)
+                            .map(|e| graph.links[keys[e]].clone())
+                            .collect();
+                        component.sort_by(|a, b| {
+                            graph
+                                .link_key(&a.source, &a.target)
+                                .cmp(&graph.link_key(&b.source, &b.target))
+                        });
+                        components.push(component);
+                    }
+                }
+            }
+        }
+        components
+    }
+
+    // depth first search from start (depth 0) that does not go deeper than
+    // max_depth, returns every node within the bound once in discovery order.
+    // a node first reached through a long branch is expanded again when a
+    // shorter branch reaches it later, so nothing within the bound is missed
+    pub fn dfs_limited(graph: &Graph, start: &str, max_depth: usize) -> Vec<Node> {
+        let s = match graph.node_index(start) {
+            Some(s) => s,
+            None => return Vec::new(),
+        };
+        let mut adj = index_adjacency(graph);
+        adj.iter_mut()
+            .for_each(|neighbors| neighbors.sort_by_key(|&(v, _)| v));
+        let mut depth = vec![usize::MAX; adj.len()];
+        let mut result = Vec::new();
+        let mut stack = vec![(s, 0)];
+        while let Some((u, d)) = stack.pop() {
+            if d >= depth[u] {
+                continue;
+            }
+            if depth[u] == usize::MAX {
+                result.push(graph.nodes[u].clone());
+            }
+            depth[u] = d;
+            if d < max_depth {
+                // reversed so the lowest index neighbor is explored first
+                adj[u].iter().rev().for_each(|&(v, _)| {
+                    if d + 1 < depth[v] {
+                        stack.push((v, d + 1));
+                    }
+                });
+            }
+        }
+        result
+    }
+
+    // johnson's all pairs shortest paths: links are reweighted with a
+    // bellman-ford potential so they become non negative, then dijkstra runs
+    // from every node in parallel. link weights are used when the graph is
+    // weighted, otherwise hop counts. only reachable (source, target) pairs
+    // are returned, errors when the graph has a negative cycle
+    pub fn johnson(graph: &Graph) -> Result<HashMap<(String, String), f64>, String> {
+        let adj: Vec<Vec<(usize, f64)>> = index_adjacency(graph)
+            .into_iter()
+            .map(|neighbors| {
+                neighbors
+                    .into_iter()
+                    .map(|(v, w)| (v, if graph.weighted { w } else { 1.0 }))
+                    .collect()
+            })
+            .collect();
+        let h = bellman_ford_potential(&adj)
+            .ok_or_else(|| "[ERROR] graph has a negative cycle".to_string())?;
+        let reweighted: Vec<Vec<(usize, f64)>> = adj
+            .iter()
+            .enumerate()
+            .map(|(u, neighbors)| {
+                neighbors
+                    .iter()
+                    .map(|&(v, w)| (v, (w + h[u] - h[v]).max(0.0)))
+                    .collect()
+            })
+            .collect();
+        Ok((0..adj.len())
+            .into_par_iter()
+            .flat_map_iter(|s| {
+                let dist = distances_from(&reweighted, s, true);
+                dist.into_iter()
+                    .enumerate()
+                    .filter(|(_, d)| d.is_finite())
+                    .map(|(t, d)| {
+                        let pair = (graph.nodes[s].id.clone(), graph.nodes[t].id.clone());
+                        (pair, d - h[s] + h[t])
+                    })
+                    .collect::<Vec<((String, String), f64)>>()
+            })
+            .collect())
+    }
+
+    // groups the nodes of a DAG by their longest path distance from any
+    // source node, layer 0 holds the sources. nodes keep their insertion
+    // order inside a layer, errors when the graph has a cycle
+    pub fn topological_layers(graph: &Graph) -> Result<Vec<Vec<Node>>, String> {
+        let adj = index_adjacency(graph);
+        let mut indegree = vec![0; adj.len()];
+        adj.iter().flatten().for_each(|&(v, _)| indegree[v] += 1);
+        let mut queue: VecDeque<usize> = (0..adj.len()).filter(|&u| indegree[u] == 0).collect();
+        let mut layer = vec![0; adj.len()];
+        let mut visited = 0;
+        while let Some(u) = queue.pop_front() {
+            visited += 1;
+            for &(v, _) in &adj[u] {
+                layer[v] = layer[v].max(layer[u] + 1);
+                indegree[v] -= 1;
+                if indegree[v] == 0 {
+                    queue.push_back(v);
+                }
+            }
+        }
+        if visited < adj.len() {
+            return Err("[ERROR] graph has a cycle".to_string());
+        }
+        let depth = layer.iter().max().map_or(0, |&d| d + 1);
+        Ok(layer
+            .iter()
+            .enumerate()
+            .fold(vec![Vec::new(); depth], |mut layers, (u, &d)| {
+                layers[d].push(graph.nodes[u].clone());
+                layers
+            }))
+    }
+
+    // node index => indices of the nodes linking to it
+    fn reverse_adjacency(graph: &Graph) -> Vec<Vec<usize>> {
+        let mut reverse = vec![Vec::new(); graph.nodes.len()];
+        index_adjacency(graph)
+            .iter()
+            .enumerate()
+            .for_each(|(u, neighbors)| neighbors.iter().for_each(|&(v, _)| reverse[v].push(u)));
+        reverse
+    }
+
+    // marks every node that can reach `target` through directed links,
+    // `target` itself is only marked when it sits on a cycle
+    fn ancestor_marks(reverse: &[Vec<usize>], target: usize) -> Vec<bool> {
+        let mut marked = vec![false; reverse.len()];
+        let mut stack = vec![target];
+        while let Some(u) = stack.pop() {
+            for &v in &reverse[u] {
+                if !marked[v] {
+                    marked[v] = true;
+                    stack.push(v);
+                }
+            }
+        }
+        marked
+    }
+
+    // every node with a path to node_id, in insertion order
+    pub fn ancestors(graph: &Graph, node_id: &str) -> Vec<Node> {
+        match graph.node_index(node_id) {
+            Some(t) => ancestor_marks(&reverse_adjacency(graph), t)
+                .iter()
+                .zip(graph.nodes.iter())
+                .filter(|(&m, n)| m && n.id != node_id)
+                .map(|(_, n)| n.clone())
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    // a common ancestor of a and b in a DAG that is not an ancestor of any
+    // other common ancestor. a node counts as its own ancestor, so the lca of
+    // a node and one of its descendants is the node itself. when several
+    // nodes qualify the first inserted one is returned
+    pub fn lowest_common_ancestor(graph: &Graph, a: &str, b: &str) -> Option<Node> {
+        let (ia, ib) = (graph.node_index(a)?, graph.node_index(b)?);
+        let reverse = reverse_adjacency(graph);
+        let mut of_a = ancestor_marks(&reverse, ia);
+        let mut of_b = ancestor_marks(&reverse, ib);
+        of_a[ia] = true;
+        of_b[ib] = true;
+        let common: Vec<usize> = (0..of_a.len()).filter(|&u| of_a[u] && of_b[u]).collect();
+        // one traversal per common ancestor, reused for every candidate
+        let marks: Vec<Vec<bool>> = common
+            .iter()
+            .map(|&c| ancestor_marks(&reverse, c))
+            .collect();
+        let lowest = common.iter().find(|&&c| {
+            common
+                .iter()
+                .zip(marks.iter())
+                .all(|(&other, of_other)| other == c || !of_other[c])
+        })?;
+        Some(graph.nodes[*lowest].clone())
+    }
+
+    // single source stage of brandes' algorithm: dijkstra that records every
+    // shortest path predecessor. returns (nodes in the order they were
+    // settled, predecessors, number of shortest paths from source)
+    fn shortest_path_dag(
+        adj: &[Vec<(usize, f64)>],
+        source: usize,
+        use_weights: bool,
+    ) -> (Vec<usize>, Vec<Vec<usize>>, Vec<f64>) {
+        let n = adj.len();
+        let mut dist = vec![f64::INFINITY; n];
+        let mut paths = vec![0.0; n];
+        let mut preds: Vec<Vec<usize>> = vec![Vec::new(); n];
+        let mut settled = vec![false; n];
+        let mut order = Vec::new();
+        let mut heap = BinaryHeap::new();
+        dist[source] = 0.0;
+        paths[source] = 1.0;
+        heap.push(HeapEntry(0.0, source));
+        while let Some(HeapEntry(d, u)) = heap.pop() {
+            if d > dist[u] || settled[u] {
+                continue;
+            }
+            settled[u] = true;
+            order.push(u);
+            for &(v, w) in &adj[u] {
+                let alt = d + if use_weights { w } else { 1.0 };
+                if alt < dist[v] - 1e-12 {
+                    dist[v] = alt;
+                    paths[v] = paths[u];
+                    preds[v] = vec![u];
+                    heap.push(HeapEntry(alt, v));
+                } else if (alt - dist[v]).abs() <= 1e-12 && v != u {
+                    paths[v] += paths[u];
+                    preds[v].push(u);
+                }
+            }
+        }
+        (order, preds, paths)
+    }
+
+    // brandes' betweenness with dijkstra, always using the link weights
+    // whatever the `weighted` flag says, so a cheap detour through several
+    // nodes beats a single expensive link. unlike hop count betweenness the
+    // intermediate nodes of such detours get the credit. scores are not
+    // normalized, pairs are counted once on undirected graphs
+    pub fn weighted_betweenness_centrality(graph: &Graph) -> HashMap<String, f64> {
+        let adj = index_adjacency(graph);
+        let n = adj.len();
+        let scores = (0..n)
+            .into_par_iter()
+            .map(|s| {
+                let (mut order, preds, paths) = shortest_path_dag(&adj, s, true);
+                let mut delta = vec![0.0; n];
+                let mut partial = vec![0.0; n];
+                while let Some(w) = order.pop() {
+                    for &v in &preds[w] {
+                        delta[v] += paths[v] / paths[w] * (1.0 + delta[w]);
+                    }
+                    if w != s {
+                        partial[w] += delta[w];
+                    }
+                }
+                partial
+            })
+            .reduce(
+                || vec![0.0; n],
+                |mut acc, partial| {
+                    acc.iter_mut().zip(partial).for_each(|(a, p)| *a += p);
+                    acc
+                },
+            );
+        let scale = if graph.directed { 1.0 } else { 0.5 };
+        graph
+            .nodes
+            .iter()
+            .zip(scores)
+            .map(|(node, score)| (node.id.clone(), score * scale))
+            .collect()
+    }
+
+    // brandes' algorithm crediting links instead of nodes: the number of
+    // shortest paths between all pairs that run through each link, keyed by
+    // link key. paths follow the link weights when the graph is weighted and
+    // hop counts otherwise, pairs are counted once on undirected graphs
+    pub fn edge_betweenness_centrality(graph: &Graph) -> HashMap<String, f64> {
+        let adj = index_adjacency(graph);
+        let n = adj.len();
+        let scale = if graph.directed { 1.0 } else { 0.5 };
+        let mut scores: HashMap<String, f64> =
+            graph.links.keys().map(|k| (k.clone(), 0.0)).collect();
+        let credits: Vec<((usize, usize), f64)> = (0..n)
+            .into_par_iter()
+            .flat_map_iter(|s| {
+                let (mut order, preds, paths) = shortest_path_dag(&adj, s, graph.weighted);
+                let mut delta = vec![0.0; n];
+                let mut credits = Vec::new();
+                while let Some(w) = order.pop() {
+                    for &v in &preds[w] {
+                        let c = paths[v] / paths[w] * (1.0 + delta[w]);
+                        credits.push(((v, w), c));
+                        delta[v] += c;
+                    }
+                }
+                credits
+            })
+            .collect();
+        credits.into_iter().for_each(|((v, w), c)| {
+            let key = graph.link_key(&graph.nodes[v].id, &graph.nodes[w].id);
+            *scores.entry(key).or_insert(0.0) += c * scale;
+        });
+        scores
+    }
+
+    // number of nodes reachable from s divided by the sum of the distances
+    // to them, 0.0 when nothing is reachable
+    fn closeness_from(adj: &[Vec<(usize, f64)>], s: usize, use_weights: bool) -> f64 {
+        let (reached, total) = distances_from(adj, s, use_weights)
+            .iter()
+            .enumerate()
+            .filter(|&(t, d)| t != s && d.is_finite())
+            .fold((0, 0.0), |(count, sum), (_, d)| (count + 1, sum + d));
+        if total > 0.0 {
+            reached as f64 / total
+        } else {
+            0.0
+        }
+    }
+
+    // closeness of node_id using dijkstra distances over the link weights,
+    // whatever the `weighted` flag says: the number of reachable nodes divided
+    // by the sum of the weighted distances to them. `closeness_centrality`
+    // instead follows the flag and counts hops on unweighted graphs. 0.0
+    // when nothing is reachable
+    pub fn weighted_closeness_centrality(graph: &Graph, node_id: &str) -> f64 {
+        match graph.node_index(node_id) {
+            Some(s) => closeness_from(&index_adjacency(graph), s, true),
+            None => 0.0,
+        }
+    }
+
+    // closeness of node_id: reachable nodes over the sum of the distances to
+    // them, with link weights on weighted graphs and hop counts otherwise
+    pub fn closeness_centrality(graph: &Graph, node_id: &str) -> f64 {
+        match graph.node_index(node_id) {
+            Some(s) => closeness_from(&index_adjacency(graph), s, graph.weighted),
+            None => 0.0,
+        }
+    }
+
+    // closeness_centrality of every node, the adjacency is built once and
+    // the sources are processed in parallel
+    pub fn closeness_centrality_all(graph: &Graph) -> HashMap<String, f64> {
+        let adj = index_adjacency(graph);
+        (0..adj.len())
+            .into_par_iter()
+            .map(|s| {
+                (
+                    graph.nodes[s].id.clone(),
+                    closeness_from(&adj, s, graph.weighted),
+                )
+            })
+            .collect()
+    }
+
+    // hop distance from every node to the nearest of the sources, found with
+    // one breadth first search seeded with all sources at distance 0.
+    // unknown sources are ignored and unreachable nodes are left out
+    pub fn multi_source_bfs(graph: &Graph, sources: &[String]) -> HashMap<String, usize> {
+        let adj = index_adjacency(graph);
+        let mut dist = vec![usize::MAX; adj.len()];
+        let mut queue = VecDeque::new();
+        sources
+            .iter()
+            .filter_map(|id| graph.node_index(id))
+            .for_each(|s| {
+                if dist[s] == usize::MAX {
+                    dist[s] = 0;
+                    queue.push_back(s);
+                }
+            });
+        while let Some(u) = queue.pop_front() {
+            for &(v, _) in &adj[u] {
+                if dist[v] == usize::MAX {
+                    dist[v] = dist[u] + 1;
+                    queue.push_back(v);
+                }
+            }
+        }
+        dist.iter()
+            .enumerate()
+            .filter(|(_, &d)| d != usize::MAX)
+            .map(|(u, &d)| (graph.nodes[u].id.clone(), d))
+            .collect()
+    }
+
+    // seeded random walk from start that jumps back to start with
+    // probability restart_prob before every step, and also when it reaches
+    // a node without out links. steps pick a neighbor proportionally to the
+    // link weight on weighted graphs and uniformly otherwise. returns the
+    // share of the iterations spent on each visited node, an approximation
+    // of personalized pagerank. panics when restart_prob is not in (0, 1)
+    pub fn random_walk_with_restart(
+        graph: &Graph,
+        start: &str,
+        restart_prob: f64,
+        iterations: usize,
+        seed: u64,
+    ) -> HashMap<String, f64> {
+        assert!(
+            restart_prob > 0.0 && restart_prob < 1.0,
+            "[ERROR] restart probability {} is not in (0, 1)",
+            restart_prob
+        );
+        let s = match graph.node_index(start) {
+            Some(s) if iterations > 0 => s,
+            _ => return HashMap::new(),
+        };
+        let adj = index_adjacency(graph);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut visits = vec![0usize; adj.len()];
+        let mut current = s;
+        for _ in 0..iterations {
+            visits[current] += 1;
+            let neighbors = &adj[current];
+            if neighbors.is_empty() || rng.gen::<f64>() < restart_prob {
+                current = s;
+                continue;
+            }
+            let total: f64 = neighbors.iter().map(|&(_, w)| w).sum();
+            current = if graph.weighted && total > 0.0 {
+                let mut r = rng.gen::<f64>() * total;
+                neighbors
+                    .iter()
+                    .find(|&&(_, w)| {
+                        r -= w;
+                        r < 0.0
+                    })
+                    .unwrap_or(&neighbors[neighbors.len() - 1])
+                    .0
+            } else {
+                neighbors[rng.gen_range(0..neighbors.len())].0
+            };
+        }
+        visits
+            .iter()
+            .enumerate()
+            .filter(|(_, &v)| v > 0)
+            .map(|(u, &v)| (graph.nodes[u].id.clone(), v as f64 / iterations as f64))
+            .collect()
+    }
+
+    // state of johnson's cycle search for one start node `s`, only nodes
+    // with index >= s take part
+    struct CycleSearch<'a> {
+        adj: &'a [Vec<usize>],
+        s: usize,
+        blocked: Vec<bool>,
+        // blocked_by[w] holds the nodes to unblock once w gets unblocked
+        blocked_by: Vec<HashSet<usize>>,
+        stack: Vec<usize>,
+        cycles: Vec<Vec<usize>>,
+    }
+
+    impl<'a> CycleSearch<'a> {
+        fn unblock(&mut self, u: usize) {
+            self.blocked[u] = false;
+            let waiting: Vec<usize> = self.blocked_by[u].drain().collect();
+            for w in waiting {
+                if self.blocked[w] {
+                    self.unblock(w);
+                }
+            }
+        }
+
+        fn circuit(&mut self, v: usize) -> bool {
+            let mut found = false;
+            self.stack.push(v);
+            self.blocked[v] = true;
+            let (adj, s) = (self.adj, self.s);
+            for &w in adj[v].iter().filter(|&&w| w >= s) {
+                if w == s {
+                    self.cycles.push(self.stack.clone());
+                    found = true;
+                } else if !self.blocked[w] && self.circuit(w) {
+                    found = true;
+                }
+            }
+            if found {
+                self.unblock(v);
+            } else {
+                adj[v].iter().filter(|&&w| w >= s).for_each(|&w| {
+                    self.blocked_by[w].insert(v);
+                });
+            }
+            self.stack.pop();
+            found
+        }
+    }
+
+    // every elementary cycle of a directed graph with johnson's algorithm,
+    // each cycle starts at its earliest inserted node and does not repeat
+    // it at the end, self loops are cycles of one node. the number of cycles
+    // can grow exponentially with the graph size, so this is meant for
+    // sparse or small graphs. undirected links count as two directed links
+    // and therefore form a cycle of two nodes each
+    pub fn simple_cycles(graph: &Graph) -> Vec<Vec<Node>> {
+        let adj: Vec<Vec<usize>> = index_adjacency(graph)
+            .into_iter()
+            .map(|neighbors| {
+                let mut ids: Vec<usize> = neighbors.into_iter().map(|(v, _)| v).collect();
+                ids.sort_unstable();
+                ids.dedup();
+                ids
+            })
+            .collect();
+        let mut cycles = Vec::new();
+        for s in 0..adj.len() {
+            let mut search = CycleSearch {
+                adj: &adj,
+                s,
+                blocked: vec![false; adj.len()],
+                blocked_by: vec![HashSet::new(); adj.len()],
+                stack: Vec::new(),
+                cycles: Vec::new(),
+            };
+            search.circuit(s);
+            cycles.append(&mut search.cycles);
+        }
+        cycles
+            .iter()
+            .map(|cycle| cycle.iter().map(|&u| graph.nodes[u].clone()).collect())
+            .collect()
+    }
+
+    // girvan-newman divisive clustering: the link with the highest edge
+    // betweenness is removed from a copy of the graph until it falls apart
+    // into at least target_communities connected components (or runs out of
+    // links). ties go to the smallest link key. communities are sorted by
+    // node order, as are the nodes inside them
+    pub fn girvan_newman(graph: &Graph, target_communities: usize) -> Vec<Vec<Node>> {
+        let mut g = graph.clone();
+        loop {
+            let components = g.connected_components();
+            if components.len() >= target_communities || g.links.is_empty() {
+                let mut communities: Vec<Vec<Node>> = components
+                    .into_iter()
+                    .map(|mut c| {
+                        c.sort_by_key(|n| graph.nodes_map[&n.id]);
+                        c
+                    })
+                    .collect();
+                communities.sort_by_key(|c| graph.nodes_map[&c[0].id]);
+                return communities;
+            }
+            let scores = edge_betweenness_centrality(&g);
+            let (key, _) = scores
+                .iter()
+                .max_by(|(ka, a), (kb, b)| a.total_cmp(b).then(kb.cmp(ka)))
+                .unwrap();
+            let l = g.links[key].clone();
+            g.remove_link(&l.source, &l.target);
+        }
+    }
+
+    // breadth first forest over the undirected view, roots are taken in node
+    // order and neighbors are visited by index. returns each node's parent
+    // in the forest, None for the roots
+    fn bfs_forest(graph: &Graph) -> Vec<Option<usize>> {
+        let mut adj = undirected_adjacency(graph);
+        adj.iter_mut().for_each(|neighbors| {
+            neighbors.sort_unstable();
+            neighbors.dedup();
+        });
+        let mut parent = vec![None; adj.len()];
+        let mut seen = vec![false; adj.len()];
+        for root in 0..adj.len() {
+            if seen[root] {
+                continue;
+            }
+            seen[root] = true;
+            let mut queue = VecDeque::from(vec![root]);
+            while let Some(u) = queue.pop_front() {
+                for &v in &adj[u] {
+                    if !seen[v] {
+                        seen[v] = true;
+                        parent[v] = Some(u);
+                        queue.push_back(v);
+                    }
+                }
+            }
+        }
+        parent
+    }
+
+    // the link joining nodes u and v in either direction
+    fn link_between(graph: &Graph, u: usize, v: usize) -> Option<&Link> {
+        let (a, b) = (&graph.nodes[u].id, &graph.nodes[v].id);
+        graph
+            .links
+            .get(&graph.link_key(a, b))
+            .or_else(|| graph.links.get(&graph.link_key(b, a)))
+    }
+
+    // links of a breadth first spanning tree for every connected component,
+    // taking links both ways and ignoring weights. a forest has
+    // nodes - components links
+    pub fn spanning_forest(graph: &Graph) -> Vec<Link> {
+        bfs_forest(graph)
+            .iter()
+            .enumerate()
+            .filter_map(|(v, p)| p.and_then(|u| link_between(graph, u, v)).cloned())
+            .collect()
+    }
+
+    // fundamental cycle basis of the undirected view: every link outside the
+    // breadth first spanning forest closes exactly one cycle with the tree
+    // path between its endpoints. a cycle lists its nodes in order starting
+    // at the source of the closing link, self loops give one node cycles.
+    // there are links - nodes + components cycles
+    pub fn cycle_basis(graph: &Graph) -> Vec<Vec<Node>> {
+        let parent = bfs_forest(graph);
+        let tree: HashSet<String> = parent
+            .iter()
+            .enumerate()
+            .filter_map(|(v, p)| p.and_then(|u| link_between(graph, u, v)))
+            .map(|l| graph.link_key(&l.source, &l.target))
+            .collect();
+        let depth: Vec<usize> = (0..parent.len())
+            .map(|mut u| {
+                let mut d = 0;
+                while let Some(p) = parent[u] {
+                    u = p;
+                    d += 1;
+                }
+                d
+            })
+            .collect();
+        let mut keys: Vec<&String> = graph.links.keys().filter(|k| !tree.contains(*k)).collect();
+        keys.sort();
+        keys.iter()
+            .map(|k| {
+                let l = &graph.links[*k];
+                let (mut u, mut v) = (graph.nodes_map[&l.source], graph.nodes_map[&l.target]);
+                // climb from both ends until the paths meet
+                let (mut up, mut down) = (vec![u], vec![v]);
+                while u != v {
+                    if depth[u] >= depth[v] {
+                        u = parent[u].unwrap();
+                        up.push(u);
+                    } else {
+                        v = parent[v].unwrap();
+                        down.push(v);
+                    }
+                }
+                down.pop();
+                up.extend(down.into_iter().rev());
+                up.iter().map(|&i| graph.nodes[i].clone()).collect()
+            })
+            .collect()
+    }
+
+    // node2vec second order random walk of up to `length` nodes including
+    // start. after the first step, a move from the current node to x is
+    // weighted 1/p when x is the previous node, 1 when x neighbors the
+    // previous node and 1/q otherwise, times the link weight on weighted
+    // graphs. small p keeps the walk close to where it came from, small q
+    // pushes it outward. stops early at a node without out links, panics
+    // when p or q is not positive
+    pub fn biased_walk(
+        graph: &Graph,
+        start: &str,
+        length: usize,
+        p: f64,
+        q: f64,
+        seed: u64,
+    ) -> Vec<Node> {
+        assert!(p > 0.0 && q > 0.0, "[ERROR] p and q must be positive");
+        let s = match graph.node_index(start) {
+            Some(s) if length > 0 => s,
+            _ => return Vec::new(),
+        };
+        let mut adj = index_adjacency(graph);
+        adj.iter_mut()
+            .for_each(|neighbors| neighbors.sort_by_key(|&(v, _)| v));
+        let linked: Vec<HashSet<usize>> = adj
+            .iter()
+            .map(|neighbors| neighbors.iter().map(|&(v, _)| v).collect())
+            .collect();
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut walk = vec![s];
+        while walk.len() < length {
+            let current = walk[walk.len() - 1];
+            let previous = if walk.len() > 1 {
+                Some(walk[walk.len() - 2])
+            } else {
+                None
+            };
+            let weights: Vec<f64> = adj[current]
+                .iter()
+                .map(|&(x, w)| {
+                    let bias = match previous {
+                        Some(prev) if x == prev => 1.0 / p,
+                        Some(prev) if linked[prev].contains(&x) => 1.0,
+                        Some(_) => 1.0 / q,
+                        None => 1.0,
+                    };
+                    bias * if graph.weighted { w } else { 1.0 }
+                })
+                .collect();
+            let total: f64 = weights.iter().sum();
+            if total <= 0.0 {
+                break;
+            }
+            let mut r = rng.gen::<f64>() * total;
+            let picked = weights
+                .iter()
+                .position(|&w| {
+                    r -= w;
+                    r < 0.0
+                })
+                .unwrap_or(weights.len() - 1);
+            walk.push(adj[current][picked].0);
+        }
+        walk.iter().map(|&u| graph.nodes[u].clone()).collect()
+    }
+
     #[cfg(test)]
     mod tests {
         use super::*;
 
+        fn help_create_graph(links: &[(&str, &str, f64)], directed: bool) -> Graph {
+            let mut g = Graph::new();
+            g.directed = directed;
+            links.iter().for_each(|(s, t, w)| {
+                g.add_link(&Link::new(s.to_string(), t.to_string(), "".to_string(), *w))
+                    .unwrap();
+            });
+            g
+        }
+
         #[test]
         fn test_dijkstra_shortest() {
             let mut g = Graph::new();
@@ -107,34 +1641,42 @@ pub mod algo {
             let n2 = Node::new("b".to_string(), "b".to_string());
             let n3 = Node::new("c".to_string(), "c".to_string());
             let n4 = Node::new("d".to_string(), "d".to_string());
-            g.add_node(&n1);
-            g.add_node(&n2);
-            g.add_node(&n3);
-            g.add_node(&n4);
+            g.add_node(&n1).unwrap();
+            g.add_node(&n2).unwrap();
+            g.add_node(&n3).unwrap();
+            g.add_node(&n4).unwrap();
             g.add_link(&Link {
                 source: "a".to_string(),
                 target: "b".to_string(),
                 label: "".to_string(),
                 weight: 1.0,
-            });
+                ..Default::default()
+            })
+            .unwrap();
             g.add_link(&Link {
                 source: "b".to_string(),
                 target: "c".to_string(),
                 label: "".to_string(),
                 weight: 1.0,
-            });
+                ..Default::default()
+            })
+            .unwrap();
             g.add_link(&Link {
                 source: "c".to_string(),
                 target: "d".to_string(),
                 label: "".to_string(),
                 weight: 1.0,
-            });
+                ..Default::default()
+            })
+            .unwrap();
             g.add_link(&Link {
                 source: "a".to_string(),
                 target: "c".to_string(),
                 label: "".to_string(),
                 weight: 1.0,
-            });
+                ..Default::default()
+            })
+            .unwrap();
             assert_eq!(
                 dijkstra_shortest(&g, "a", "d"),
                 vec![n1.clone(), n3, n4.clone()]
@@ -144,9 +1686,927 @@ pub mod algo {
                 target: "d".to_string(),
                 label: "".to_string(),
                 weight: 1.0,
-            });
+                ..Default::default()
+            })
+            .unwrap();
             assert_eq!(dijkstra_shortest(&g, "a", "d"), vec![n1, n4]);
             // TODO: test weighted
         }
+
+        #[test]
+        fn test_wiener_index_and_average_path_length() {
+            // path a-b-c-d plus a smaller component that is ignored
+            let g = help_create_graph(
+                &[
+                    ("a", "b", 1.0),
+                    ("b", "c", 1.0),
+                    ("c", "d", 1.0),
+                    ("x", "y", 1.0),
+                ],
+                false,
+            );
+            // 1 + 2 + 3 + 1 + 2 + 1
+            assert_eq!(wiener_index(&g), 10.0);
+            assert_eq!(average_path_length(&g), 10.0 / 6.0);
+            assert_eq!(wiener_index(&Graph::new()), 0.0);
+            assert_eq!(average_path_length(&Graph::new()), 0.0);
+        }
+
+        #[test]
+        fn test_has_negative_cycle() {
+            let mut g = help_create_graph(
+                &[
+                    ("a", "b", 1.0),
+                    ("b", "c", -2.0),
+                    ("c", "a", 0.5),
+                    ("c", "d", 4.0),
+                ],
+                true,
+            );
+            g.weighted = true;
+            assert!(has_negative_cycle(&g));
+            let g = help_create_graph(
+                &[
+                    ("a", "b", 1.0),
+                    ("b", "c", 2.0),
+                    ("c", "a", 0.5),
+                    ("a", "d", 3.0),
+                ],
+                true,
+            );
+            assert!(!has_negative_cycle(&g));
+            // a negative link on its own is not a cycle in a directed graph
+            let g = help_create_graph(&[("a", "b", -1.0)], true);
+            assert!(!has_negative_cycle(&g));
+            assert!(!has_negative_cycle(&Graph::new()));
+        }
+
+        #[test]
+        fn test_eulerian() {
+            // square with a diagonal: b and d have odd degree
+            let mut g = help_create_graph(
+                &[
+                    ("a", "b", 1.0),
+                    ("b", "c", 1.0),
+                    ("c", "d", 1.0),
+                    ("d", "a", 1.0),
+                    ("b", "d", 1.0),
+                ],
+                false,
+            );
+            assert!(!has_eulerian_circuit(&g));
+            assert!(has_eulerian_path(&g));
+            let path = eulerian_path(&g);
+            assert_eq!(path.len(), 6);
+            assert!(path[0].id == "b" || path[0].id == "d");
+            for pair in path.windows(2) {
+                assert!(
+                    g.get_link(&pair[0].id, &pair[1].id).is_some()
+                        || g.get_link(&pair[1].id, &pair[0].id).is_some()
+                );
+            }
+            // seven bridges style: four odd nodes
+            g.add_link(&Link::new(
+                "a".to_string(),
+                "c".to_string(),
+                "".to_string(),
+                1.0,
+            ))
+            .unwrap();
+            assert!(!has_eulerian_path(&g));
+            assert!(eulerian_path(&g).is_empty());
+
+            // directed cycle
+            let g = help_create_graph(&[("a", "b", 1.0), ("b", "c", 1.0), ("c", "a", 1.0)], true);
+            assert!(has_eulerian_circuit(&g));
+            let path = eulerian_path(&g);
+            assert_eq!(path.len(), 4);
+            assert_eq!(path.first(), path.last());
+            // directed path a -> b -> c
+            let g = help_create_graph(&[("a", "b", 1.0), ("b", "c", 1.0)], true);
+            assert!(!has_eulerian_circuit(&g));
+            let ids: Vec<String> = eulerian_path(&g).into_iter().map(|n| n.id).collect();
+            assert_eq!(ids, vec!["a", "b", "c"]);
+        }
+
+        #[test]
+        fn test_is_reachable() {
+            let mut g =
+                help_create_graph(&[("a", "b", 1.0), ("b", "c", 1.0), ("x", "y", 1.0)], true);
+            assert!(is_reachable(&g, "a", "c"));
+            assert!(is_reachable(&g, "a", "a"));
+            assert!(!is_reachable(&g, "c", "a"));
+            assert!(!is_reachable(&g, "a", "y"));
+            assert!(!is_reachable(&g, "a", "missing"));
+            g.set_directed(false);
+            assert!(is_reachable(&g, "c", "a"));
+        }
+
+        #[test]
+        fn test_distance_matrix() {
+            let mut g = help_create_graph(
+                &[
+                    ("a", "b", 2.0),
+                    ("b", "c", 1.0),
+                    ("a", "c", 5.0),
+                    ("x", "y", 1.0),
+                ],
+                false,
+            );
+            g.weighted = true;
+            let (m, ids) = distance_matrix(&g);
+            assert_eq!(ids, vec!["a", "b", "c", "x", "y"]);
+            for (i, row) in m.iter().enumerate() {
+                assert_eq!(row[i], 0.0);
+                for (j, d) in row.iter().enumerate() {
+                    assert_eq!(*d, m[j][i]);
+                }
+            }
+            assert_eq!(m[0][2], 3.0);
+            assert_eq!(m[0][3], f64::INFINITY);
+        }
+
+        #[test]
+        fn test_dominator_tree() {
+            // if/else diamond followed by a loop
+            let g = help_create_graph(
+                &[
+                    ("entry", "then", 1.0),
+                    ("entry", "else", 1.0),
+                    ("then", "join", 1.0),
+                    ("else", "join", 1.0),
+                    ("join", "body", 1.0),
+                    ("body", "join", 1.0),
+                    ("join", "exit", 1.0),
+                    ("dead", "exit", 1.0),
+                ],
+                true,
+            );
+            let idom = dominator_tree(&g, "entry");
+            assert_eq!(idom.len(), 5);
+            assert_eq!(idom["then"], "entry");
+            assert_eq!(idom["else"], "entry");
+            assert_eq!(idom["join"], "entry");
+            assert_eq!(idom["body"], "join");
+            assert_eq!(idom["exit"], "join");
+            assert!(!idom.contains_key("entry"));
+            assert!(!idom.contains_key("dead"));
+            assert!(dominator_tree(&g, "missing").is_empty());
+        }
+
+        #[test]
+        fn test_second_shortest_path() {
+            let mut g = help_create_graph(
+                &[
+                    ("a", "b", 1.0),
+                    ("b", "d", 1.0),
+                    ("a", "c", 2.0),
+                    ("c", "d", 2.0),
+                    ("a", "e", 5.0),
+                    ("e", "d", 5.0),
+                ],
+                true,
+            );
+            g.weighted = true;
+            let ids = |path: Vec<Node>| path.into_iter().map(|n| n.id).collect::<Vec<String>>();
+            assert_eq!(ids(dijkstra_shortest(&g, "a", "d")), vec!["a", "b", "d"]);
+            assert_eq!(ids(second_shortest_path(&g, "a", "d")), vec!["a", "c", "d"]);
+            // a single route has no alternative
+            let g = help_create_graph(&[("a", "b", 1.0), ("b", "c", 1.0)], false);
+            assert!(second_shortest_path(&g, "a", "c").is_empty());
+            assert!(second_shortest_path(&g, "a", "missing").is_empty());
+        }
+
+        #[test]
+        fn test_shortest_paths_from() {
+            let mut g = help_create_graph(
+                &[
+                    ("depot", "a", 1.0),
+                    ("a", "b", 1.0),
+                    ("depot", "b", 5.0),
+                    ("b", "c", 2.0),
+                    ("x", "depot", 1.0),
+                ],
+                true,
+            );
+            g.weighted = true;
+            let targets: Vec<String> = ["a", "b", "c", "x", "missing", "depot"]
+                .iter()
+                .map(|t| t.to_string())
+                .collect();
+            let paths = shortest_paths_from(&g, "depot", &targets);
+            assert_eq!(paths.len(), targets.len());
+            for t in &targets {
+                assert_eq!(paths[t], dijkstra_shortest(&g, "depot", t));
+            }
+            assert_eq!(paths["c"].len(), 4);
+            assert!(paths["x"].is_empty());
+        }
+
+        #[test]
+        fn test_center_and_periphery() {
+            let ids = |nodes: Vec<Node>| nodes.into_iter().map(|n| n.id).collect::<Vec<String>>();
+            let g = help_create_graph(
+                &[
+                    ("a", "b", 1.0),
+                    ("b", "c", 1.0),
+                    ("c", "d", 1.0),
+                    ("d", "e", 1.0),
+                    ("x", "y", 1.0),
+                ],
+                false,
+            );
+            assert_eq!(ids(center(&g)), vec!["c"]);
+            assert_eq!(ids(periphery(&g)), vec!["a", "e"]);
+            let g = help_create_graph(&[("a", "b", 1.0), ("b", "c", 1.0), ("c", "d", 1.0)], false);
+            assert_eq!(ids(center(&g)), vec!["b", "c"]);
+            assert!(center(&Graph::new()).is_empty());
+        }
+
+        #[test]
+        fn test_min_cut() {
+            let mut g = help_create_graph(
+                &[
+                    ("a", "b", 3.0),
+                    ("b", "c", 3.0),
+                    ("c", "a", 3.0),
+                    ("x", "y", 3.0),
+                    ("y", "z", 3.0),
+                    ("z", "x", 3.0),
+                    ("a", "x", 1.0),
+                    ("b", "y", 0.5),
+                ],
+                false,
+            );
+            g.weighted = true;
+            let (cut, side) = min_cut(&g);
+            assert_eq!(cut, 1.5);
+            let mut ids: Vec<String> = side.into_iter().map(|n| n.id).collect();
+            ids.sort();
+            assert!(ids == vec!["a", "b", "c"] || ids == vec!["x", "y", "z"]);
+            // unweighted, the cheapest cut isolates a single degree-2 node
+            g.weighted = false;
+            assert_eq!(min_cut(&g).0, 2.0);
+            assert_eq!(min_cut(&Graph::new()), (0.0, Vec::new()));
+        }
+
+        #[test]
+        fn test_maximal_independent_set() {
+            let g = help_create_graph(
+                &[
+                    ("a", "b", 1.0),
+                    ("b", "c", 1.0),
+                    ("c", "d", 1.0),
+                    ("d", "e", 1.0),
+                ],
+                true,
+            );
+            let set: Vec<String> = maximal_independent_set(&g)
+                .into_iter()
+                .map(|n| n.id)
+                .collect();
+            assert_eq!(set, vec!["a", "c", "e"]);
+            let adjacent =
+                |a: &str, b: &str| g.get_link(a, b).is_some() || g.get_link(b, a).is_some();
+            for a in &set {
+                for b in &set {
+                    assert!(!adjacent(a, b));
+                }
+            }
+            // every node outside the set has a neighbor inside it
+            for n in &g.nodes {
+                assert!(set.contains(&n.id) || set.iter().any(|s| adjacent(s, &n.id)));
+            }
+        }
+
+        #[test]
+        fn test_vertex_cover_approx() {
+            let g = help_create_graph(
+                &[
+                    ("a", "b", 1.0),
+                    ("b", "c", 1.0),
+                    ("c", "d", 1.0),
+                    ("b", "e", 1.0),
+                    ("e", "e", 1.0),
+                    ("f", "g", 1.0),
+                ],
+                false,
+            );
+            let cover: HashSet<String> =
+                vertex_cover_approx(&g).into_iter().map(|n| n.id).collect();
+            for l in g.links.values() {
+                assert!(cover.contains(&l.source) || cover.contains(&l.target));
+            }
+            // the optimum is {b, c, e, f}, the matching bound is twice that
+            assert!(cover.len() <= 8);
+            assert_eq!(vertex_cover_approx(&g), vertex_cover_approx(&g));
+            assert!(vertex_cover_approx(&Graph::new()).is_empty());
+        }
+
+        #[test]
+        fn test_biconnected_components() {
+            // two triangles sharing the cut vertex c
+            let mut g = help_create_graph(
+                &[
+                    ("a", "b", 1.0),
+                    ("b", "c", 1.0),
+                    ("c", "a", 1.0),
+                    ("c", "d", 1.0),
+                    ("d", "e", 1.0),
+                    ("e", "c", 1.0),
+                ],
+                false,
+            );
+            let components = biconnected_components(&g);
+            assert_eq!(components.len(), 2);
+            let mut sets: Vec<Vec<String>> = components
+                .iter()
+                .map(|c| {
+                    let mut ids: Vec<String> = c
+                        .iter()
+                        .flat_map(|l| vec![l.source.clone(), l.target.clone()])
+                        .collect();
+                    ids.sort();
+                    ids.dedup();
+                    ids
+                })
+                .collect();
+            sets.sort();
+            assert_eq!(sets, vec![vec!["a", "b", "c"], vec!["c", "d", "e"]]);
+            // a bridge is a component of its own
+            g.add_link(&Link::new(
+                "e".to_string(),
+                "f".to_string(),
+                "".to_string(),
+                1.0,
+            ))
+            .unwrap();
+            let components = biconnected_components(&g);
+            assert_eq!(components.len(), 3);
+            assert!(components
+                .iter()
+                .any(|c| c.len() == 1 && c[0].target == "f"));
+            assert_eq!(components.iter().map(|c| c.len()).sum::<usize>(), 7);
+        }
+
+        #[test]
+        fn test_dfs_limited() {
+            let g = help_create_graph(
+                &[
+                    ("hub", "a", 1.0),
+                    ("hub", "b", 1.0),
+                    ("a", "c", 1.0),
+                    ("c", "d", 1.0),
+                    ("b", "d", 1.0),
+                    ("d", "e", 1.0),
+                    ("x", "hub", 1.0),
+                ],
+                true,
+            );
+            let ids = |nodes: Vec<Node>| nodes.into_iter().map(|n| n.id).collect::<Vec<String>>();
+            assert_eq!(ids(dfs_limited(&g, "hub", 0)), vec!["hub"]);
+            assert_eq!(ids(dfs_limited(&g, "hub", 1)), vec!["hub", "a", "b"]);
+            // d is first seen at depth 3 through a and c, then at depth 2
+            // through b, which brings e within reach
+            let mut found = ids(dfs_limited(&g, "hub", 3));
+            found.sort();
+            assert_eq!(found, vec!["a", "b", "c", "d", "e", "hub"]);
+            assert!(dfs_limited(&g, "missing", 3).is_empty());
+        }
+
+        #[test]
+        fn test_johnson() {
+            let mut g = help_create_graph(
+                &[
+                    ("a", "b", 4.0),
+                    ("a", "c", 1.0),
+                    ("c", "b", 2.0),
+                    ("b", "d", 1.0),
+                    ("c", "d", 5.0),
+                    ("d", "e", 3.0),
+                ],
+                true,
+            );
+            g.weighted = true;
+            // floyd-warshall reference
+            let (n, ids) = (g.nodes.len(), g.nodes.clone());
+            let mut fw = vec![vec![f64::INFINITY; n]; n];
+            (0..n).for_each(|i| fw[i][i] = 0.0);
+            g.links.values().for_each(|l| {
+                fw[g.nodes_map[&l.source]][g.nodes_map[&l.target]] = l.weight;
+            });
+            for k in 0..n {
+                for i in 0..n {
+                    for j in 0..n {
+                        fw[i][j] = fw[i][j].min(fw[i][k] + fw[k][j]);
+                    }
+                }
+            }
+            let result = johnson(&g).unwrap();
+            for i in 0..n {
+                for j in 0..n {
+                    let key = (ids[i].id.clone(), ids[j].id.clone());
+                    match result.get(&key) {
+                        Some(d) => assert!((d - fw[i][j]).abs() < 1e-9),
+                        None => assert_eq!(fw[i][j], f64::INFINITY),
+                    }
+                }
+            }
+            // negative links are fine as long as there is no negative cycle
+            g.links.get_mut("c_b").unwrap().weight = -2.0;
+            let result = johnson(&g).unwrap();
+            assert_eq!(result[&("a".to_string(), "d".to_string())], 0.0);
+            g.add_link(&Link::new(
+                "b".to_string(),
+                "c".to_string(),
+                "".to_string(),
+                1.0,
+            ))
+            .unwrap();
+            assert!(johnson(&g).is_err());
+        }
+
+        #[test]
+        fn test_topological_layers() {
+            let g = help_create_graph(
+                &[
+                    ("a", "b", 1.0),
+                    ("a", "c", 1.0),
+                    ("b", "d", 1.0),
+                    ("c", "d", 1.0),
+                    ("a", "d", 1.0),
+                    ("d", "e", 1.0),
+                ],
+                true,
+            );
+            let layers: Vec<Vec<String>> = topological_layers(&g)
+                .unwrap()
+                .iter()
+                .map(|layer| layer.iter().map(|n| n.id.clone()).collect())
+                .collect();
+            assert_eq!(
+                layers,
+                vec![
+                    vec!["a".to_string()],
+                    vec!["b".to_string(), "c".to_string()],
+                    vec!["d".to_string()],
+                    vec!["e".to_string()],
+                ]
+            );
+
+            let cyclic = help_create_graph(&[("a", "b", 1.0), ("b", "a", 1.0)], true);
+            assert!(topological_layers(&cyclic).is_err());
+        }
+
+        #[test]
+        fn test_lowest_common_ancestor() {
+            //   r
+            //  / \
+            // x   y
+            // |\  |
+            // p q s
+            let g = help_create_graph(
+                &[
+                    ("r", "x", 1.0),
+                    ("r", "y", 1.0),
+                    ("x", "p", 1.0),
+                    ("x", "q", 1.0),
+                    ("y", "s", 1.0),
+                    ("u", "v", 1.0),
+                ],
+                true,
+            );
+            let ids: Vec<String> = ancestors(&g, "q").iter().map(|n| n.id.clone()).collect();
+            assert_eq!(ids, vec!["r".to_string(), "x".to_string()]);
+            assert_eq!(lowest_common_ancestor(&g, "p", "q").unwrap().id, "x");
+            assert_eq!(lowest_common_ancestor(&g, "p", "s").unwrap().id, "r");
+            assert_eq!(lowest_common_ancestor(&g, "x", "q").unwrap().id, "x");
+            assert!(lowest_common_ancestor(&g, "p", "v").is_none());
+        }
+
+        #[test]
+        fn test_weighted_centrality() {
+            // a-d costs 10 directly, the detour through b and c costs 3
+            let g = help_create_graph(
+                &[
+                    ("a", "d", 10.0),
+                    ("a", "b", 1.0),
+                    ("b", "c", 1.0),
+                    ("c", "d", 1.0),
+                ],
+                false,
+            );
+            let betweenness = weighted_betweenness_centrality(&g);
+            assert_eq!(betweenness["a"], 0.0);
+            assert_eq!(betweenness["d"], 0.0);
+            // b lies on a-c and a-d, c on b-d and a-d
+            assert!((betweenness["b"] - 2.0).abs() < 1e-9);
+            assert!((betweenness["c"] - 2.0).abs() < 1e-9);
+
+            // distances from a: b 1, c 2, d 3
+            assert!((weighted_closeness_centrality(&g, "a") - 3.0 / 6.0).abs() < 1e-9);
+            assert_eq!(weighted_closeness_centrality(&g, "x"), 0.0);
+        }
+
+        #[test]
+        fn test_multi_source_bfs() {
+            // a - b - c - d - e - f, plus an isolated x - y
+            let g = help_create_graph(
+                &[
+                    ("a", "b", 1.0),
+                    ("b", "c", 1.0),
+                    ("c", "d", 1.0),
+                    ("d", "e", 1.0),
+                    ("e", "f", 1.0),
+                    ("x", "y", 1.0),
+                ],
+                false,
+            );
+            let dist = multi_source_bfs(&g, &["a".to_string(), "f".to_string()]);
+            assert_eq!(dist.len(), 6);
+            assert_eq!(dist["a"], 0);
+            assert_eq!(dist["b"], 1);
+            assert_eq!(dist["c"], 2);
+            assert_eq!(dist["d"], 2);
+            assert_eq!(dist["e"], 1);
+            assert_eq!(dist["f"], 0);
+            assert!(!dist.contains_key("x"));
+        }
+
+        #[test]
+        fn test_dijkstra_shortest_weighted() {
+            // the direct link is one hop but costs more than the detour
+            let g = help_create_graph(
+                &[
+                    ("a", "d", 10.0),
+                    ("a", "b", 1.0),
+                    ("b", "c", 1.0),
+                    ("c", "d", 1.0),
+                ],
+                true,
+            );
+            assert!(!g.weighted);
+            let ids = |path: Vec<Node>| path.into_iter().map(|n| n.id).collect::<Vec<String>>();
+            assert_eq!(
+                ids(dijkstra_shortest_weighted(&g, "a", "d", true)),
+                vec!["a", "b", "c", "d"]
+            );
+            assert_eq!(
+                ids(dijkstra_shortest_weighted(&g, "a", "d", false)),
+                vec!["a", "d"]
+            );
+            assert_eq!(ids(dijkstra_shortest(&g, "a", "d")), vec!["a", "d"]);
+        }
+
+        #[test]
+        fn test_reachable_set() {
+            let g = help_create_graph(
+                &[
+                    ("root", "a", 1.0),
+                    ("a", "b", 1.0),
+                    ("a", "c", 1.0),
+                    ("z", "root", 1.0),
+                    ("island", "rock", 1.0),
+                ],
+                true,
+            );
+            let expected: HashSet<String> = ["a", "b", "c"].iter().map(|s| s.to_string()).collect();
+            assert_eq!(reachable_set(&g, "a"), expected);
+            assert_eq!(reachable_set(&g, "root").len(), 4);
+            assert!(!reachable_set(&g, "root").contains("island"));
+            assert!(reachable_set(&g, "missing").is_empty());
+
+            // undirected links are followed both ways
+            let mut undirected = g.clone();
+            undirected.set_directed(false);
+            assert_eq!(reachable_set(&undirected, "b").len(), 5);
+        }
+
+        #[test]
+        fn test_shortest_path_avoiding() {
+            let g = help_create_graph(
+                &[
+                    ("a", "b", 1.0),
+                    ("b", "d", 1.0),
+                    ("a", "c", 1.0),
+                    ("c", "e", 1.0),
+                    ("e", "d", 1.0),
+                ],
+                false,
+            );
+            let ids = |path: Vec<Node>| path.into_iter().map(|n| n.id).collect::<Vec<String>>();
+            assert_eq!(
+                ids(shortest_path_avoiding(&g, "a", "d", &[])),
+                vec!["a", "b", "d"]
+            );
+            // blocked in the opposite order still closes the undirected link
+            let outage = vec![("d".to_string(), "b".to_string())];
+            assert_eq!(
+                ids(shortest_path_avoiding(&g, "a", "d", &outage)),
+                vec!["a", "c", "e", "d"]
+            );
+            let cut = vec![
+                ("b".to_string(), "d".to_string()),
+                ("e".to_string(), "d".to_string()),
+            ];
+            assert!(shortest_path_avoiding(&g, "a", "d", &cut).is_empty());
+        }
+
+        #[test]
+        fn test_contains_path_and_path_length() {
+            let g = help_create_graph(
+                &[
+                    ("a", "b", 5.0),
+                    ("b", "c", 5.0),
+                    ("a", "c", 20.0),
+                    ("c", "d", 1.0),
+                    ("x", "y", 1.0),
+                ],
+                true,
+            );
+            assert!(contains_path(&g, "a", "d"));
+            assert!(!contains_path(&g, "d", "a"));
+            assert!(!contains_path(&g, "a", "y"));
+            assert_eq!(path_length(&g, "a", "d"), Some(2));
+            assert_eq!(path_length(&g, "a", "a"), Some(0));
+            assert_eq!(path_length(&g, "d", "a"), None);
+            assert_eq!(path_length(&g, "a", "missing"), None);
+        }
+
+        #[test]
+        fn test_random_walk_with_restart() {
+            // a chain moving away from the start
+            let g = help_create_graph(
+                &[
+                    ("s", "n1", 1.0),
+                    ("n1", "n2", 1.0),
+                    ("n2", "n3", 1.0),
+                    ("n3", "n4", 1.0),
+                ],
+                false,
+            );
+            let visits = random_walk_with_restart(&g, "s", 0.3, 20000, 11);
+            assert!((visits.values().sum::<f64>() - 1.0).abs() < 1e-9);
+            assert!(visits["s"] > visits["n2"]);
+            assert!(visits["n1"] > visits["n3"]);
+            assert!(visits["n2"] > visits["n4"]);
+            assert_eq!(visits, random_walk_with_restart(&g, "s", 0.3, 20000, 11));
+            assert!(random_walk_with_restart(&g, "missing", 0.3, 100, 1).is_empty());
+        }
+
+        #[test]
+        #[should_panic]
+        fn test_random_walk_with_restart_invalid_probability() {
+            let g = help_create_graph(&[("a", "b", 1.0)], true);
+            random_walk_with_restart(&g, "a", 1.0, 10, 1);
+        }
+
+        #[test]
+        fn test_simple_cycles() {
+            // a -> b -> c -> a and b -> d -> c overlap on b and c
+            let g = help_create_graph(
+                &[
+                    ("a", "b", 1.0),
+                    ("b", "c", 1.0),
+                    ("c", "a", 1.0),
+                    ("b", "d", 1.0),
+                    ("d", "c", 1.0),
+                    ("e", "e", 1.0),
+                    ("c", "f", 1.0),
+                ],
+                true,
+            );
+            let mut cycles: Vec<Vec<String>> = simple_cycles(&g)
+                .into_iter()
+                .map(|cycle| cycle.into_iter().map(|n| n.id).collect())
+                .collect();
+            cycles.sort();
+            assert_eq!(
+                cycles,
+                vec![
+                    vec!["a".to_string(), "b".to_string(), "c".to_string()],
+                    vec![
+                        "a".to_string(),
+                        "b".to_string(),
+                        "d".to_string(),
+                        "c".to_string()
+                    ],
+                    vec!["e".to_string()],
+                ]
+            );
+            let dag = help_create_graph(&[("a", "b", 1.0), ("b", "c", 1.0), ("a", "c", 1.0)], true);
+            assert!(simple_cycles(&dag).is_empty());
+        }
+
+        #[test]
+        fn test_edge_betweenness_centrality() {
+            // two triangles joined by the c-d bridge
+            let g = help_create_graph(
+                &[
+                    ("a", "b", 1.0),
+                    ("b", "c", 1.0),
+                    ("a", "c", 1.0),
+                    ("c", "d", 1.0),
+                    ("d", "e", 1.0),
+                    ("e", "f", 1.0),
+                    ("d", "f", 1.0),
+                ],
+                false,
+            );
+            let scores = edge_betweenness_centrality(&g);
+            assert_eq!(scores.len(), 7);
+            // every one of the 3 x 3 cross pairs uses the bridge
+            assert!((scores["c_d"] - 9.0).abs() < 1e-9);
+            let bridge = scores["c_d"];
+            assert!(scores.iter().all(|(k, &v)| k == "c_d" || v < bridge));
+            assert!((scores["a_b"] - 1.0).abs() < 1e-9);
+
+            // on a weighted graph the cheap detour carries the a-c path
+            let mut weighted =
+                help_create_graph(&[("a", "b", 1.0), ("b", "c", 1.0), ("a", "c", 5.0)], true);
+            weighted.weighted = true;
+            let scores = edge_betweenness_centrality(&weighted);
+            assert_eq!(scores["a_c"], 0.0);
+            assert_eq!(scores["a_b"], 2.0);
+        }
+
+        #[test]
+        fn test_girvan_newman() {
+            let g = help_create_graph(
+                &[
+                    ("a", "b", 1.0),
+                    ("b", "c", 1.0),
+                    ("a", "c", 1.0),
+                    ("c", "d", 1.0),
+                    ("d", "e", 1.0),
+                    ("e", "f", 1.0),
+                    ("d", "f", 1.0),
+                ],
+                false,
+            );
+            let ids = |communities: Vec<Vec<Node>>| {
+                communities
+                    .into_iter()
+                    .map(|c| c.into_iter().map(|n| n.id).collect::<Vec<String>>())
+                    .collect::<Vec<Vec<String>>>()
+            };
+            assert_eq!(
+                ids(girvan_newman(&g, 2)),
+                vec![vec!["a", "b", "c"], vec!["d", "e", "f"]]
+            );
+            assert_eq!(ids(girvan_newman(&g, 1)).len(), 1);
+            // the input is left untouched
+            assert_eq!(g.links.len(), 7);
+        }
+
+        #[test]
+        fn test_reachable_count() {
+            let g = help_create_graph(
+                &[
+                    ("root", "a", 1.0),
+                    ("root", "b", 1.0),
+                    ("a", "c", 1.0),
+                    ("b", "c", 1.0),
+                ],
+                true,
+            );
+            assert_eq!(reachable_count(&g, "root"), 3);
+            assert_eq!(reachable_count(&g, "a"), 1);
+            assert_eq!(reachable_count(&g, "c"), 0);
+            assert_eq!(reachable_count(&g, "missing"), 0);
+        }
+
+        #[test]
+        fn test_closeness_centrality_all() {
+            let mut g = help_create_graph(
+                &[
+                    ("a", "b", 1.0),
+                    ("b", "c", 4.0),
+                    ("c", "d", 1.0),
+                    ("b", "e", 2.0),
+                ],
+                false,
+            );
+            let all = closeness_centrality_all(&g);
+            assert_eq!(all.len(), 5);
+            // hops from b: a 1, c 1, e 1, d 2
+            assert!((all["b"] - 4.0 / 5.0).abs() < 1e-9);
+            for id in ["a", "c", "d"].iter() {
+                assert_eq!(all[*id], closeness_centrality(&g, id));
+            }
+            g.weighted = true;
+            let all = closeness_centrality_all(&g);
+            assert_eq!(all["c"], closeness_centrality(&g, "c"));
+            assert_eq!(all["c"], weighted_closeness_centrality(&g, "c"));
+            assert!(closeness_centrality_all(&Graph::new()).is_empty());
+        }
+
+        #[test]
+        fn test_total_path_weight() {
+            let mut g =
+                help_create_graph(&[("a", "b", 2.0), ("b", "c", 0.5), ("a", "c", 5.0)], true);
+            g.weighted = true;
+            let path = dijkstra_shortest(&g, "a", "c");
+            assert_eq!(path.len(), 3);
+            assert_eq!(total_path_weight(&g, &path), Some(2.5));
+            g.weighted = false;
+            assert_eq!(total_path_weight(&g, &path), Some(2.0));
+            let reversed: Vec<Node> = path.into_iter().rev().collect();
+            assert_eq!(total_path_weight(&g, &reversed), None);
+        }
+
+        #[test]
+        fn test_spanning_forest() {
+            // square a-b-c-d-a plus a separate e-f
+            let g = help_create_graph(
+                &[
+                    ("a", "b", 1.0),
+                    ("b", "c", 1.0),
+                    ("c", "d", 1.0),
+                    ("d", "a", 1.0),
+                    ("e", "f", 1.0),
+                ],
+                true,
+            );
+            let forest = spanning_forest(&g);
+            assert_eq!(forest.len(), g.nodes.len() - 2);
+            let mut keys: Vec<String> = forest
+                .iter()
+                .map(|l| g.link_key(&l.source, &l.target))
+                .collect();
+            keys.sort();
+            assert_eq!(keys, vec!["a_b", "b_c", "d_a", "e_f"]);
+            assert!(spanning_forest(&Graph::new()).is_empty());
+        }
+
+        #[test]
+        fn test_cycle_basis() {
+            let g = help_create_graph(
+                &[
+                    ("a", "b", 1.0),
+                    ("b", "c", 1.0),
+                    ("c", "d", 1.0),
+                    ("d", "a", 1.0),
+                    ("d", "e", 1.0),
+                ],
+                false,
+            );
+            let basis = cycle_basis(&g);
+            assert_eq!(basis.len(), 1);
+            let mut ids: Vec<String> = basis[0].iter().map(|n| n.id.clone()).collect();
+            assert_eq!(ids.len(), 4);
+            ids.sort();
+            assert_eq!(ids, vec!["a", "b", "c", "d"]);
+
+            // two squares sharing an edge: links - nodes + components = 7 - 6 + 1
+            let g = help_create_graph(
+                &[
+                    ("a", "b", 1.0),
+                    ("b", "c", 1.0),
+                    ("c", "d", 1.0),
+                    ("d", "a", 1.0),
+                    ("b", "e", 1.0),
+                    ("e", "f", 1.0),
+                    ("f", "c", 1.0),
+                ],
+                false,
+            );
+            let basis = cycle_basis(&g);
+            assert_eq!(basis.len(), 2);
+            assert!(basis.iter().all(|cycle| cycle.len() == 4));
+            basis.iter().for_each(|cycle| {
+                (0..cycle.len()).for_each(|i| {
+                    let next = &cycle[(i + 1) % cycle.len()];
+                    assert!(g.edge_exists(&cycle[i].id, &next.id));
+                });
+            });
+            assert!(cycle_basis(&help_create_graph(&[("a", "b", 1.0)], false)).is_empty());
+        }
+
+        #[test]
+        fn test_biased_walk() {
+            let g = crate::generators::generators::grid_graph(6, 6, false);
+            // share of steps that go straight back to the node before
+            let returns = |walk: &[Node]| {
+                let back = walk.windows(3).filter(|w| w[0].id == w[2].id).count();
+                back as f64 / (walk.len() - 2) as f64
+            };
+            let homebody = biased_walk(&g, "2_2", 200, 0.01, 100.0, 5);
+            let explorer = biased_walk(&g, "2_2", 200, 100.0, 0.01, 5);
+            assert_eq!(homebody.len(), 200);
+            assert_eq!(homebody[0].id, "2_2");
+            assert!(returns(&homebody) > 0.9);
+            assert!(returns(&explorer) < 0.1);
+            assert!(homebody
+                .windows(2)
+                .all(|w| g.edge_exists(&w[0].id, &w[1].id)));
+            assert_eq!(
+                biased_walk(&g, "2_2", 50, 1.0, 1.0, 9),
+                biased_walk(&g, "2_2", 50, 1.0, 1.0, 9)
+            );
+
+            let sink = help_create_graph(&[("a", "b", 1.0)], true);
+            assert_eq!(biased_walk(&sink, "a", 10, 1.0, 1.0, 1).len(), 2);
+        }
     }
 }
@@ -0,0 +1,112 @@
+pub mod generators {
+    use crate::graph::graph::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use std::collections::HashSet;
+
+    // rows x cols lattice, node ids are "row_col" and every node links to its
+    // right and lower neighbor. directed grids link both ways
+    pub fn grid_graph(rows: usize, cols: usize, directed: bool) -> Graph {
+        let mut g = Graph::new();
+        g.directed = directed;
+        let id = |r: usize, c: usize| format!("{}_{}", r, c);
+        for r in 0..rows {
+            for c in 0..cols {
+                g.add_node(&Node::new(id(r, c), "".to_string())).unwrap();
+            }
+        }
+        let mut link = |s: String, t: String| {
+            g.add_link(&Link::new(s.clone(), t.clone(), "".to_string(), 1.0))
+                .unwrap();
+            if directed {
+                g.add_link(&Link::new(t, s, "".to_string(), 1.0)).unwrap();
+            }
+        };
+        for r in 0..rows {
+            for c in 0..cols {
+                if c + 1 < cols {
+                    link(id(r, c), id(r, c + 1));
+                }
+                if r + 1 < rows {
+                    link(id(r, c), id(r + 1, c));
+                }
+            }
+        }
+        g
+    }
+
+    // undirected scale free network by preferential attachment: nodes "0" to
+    // "m-1" start unlinked, then every new node links to m distinct existing
+    // nodes picked with probability proportional to their degree
+    pub fn barabasi_albert(n: usize, m: usize, seed: u64) -> Graph {
+        let mut g = Graph::new();
+        g.directed = false;
+        let mut rng = StdRng::seed_from_u64(seed);
+        (0..n.min(m)).for_each(|i| {
+            g.add_node(&Node::new(i.to_string(), "".to_string()))
+                .unwrap();
+        });
+        if m == 0 {
+            return g;
+        }
+        // every node appears once per link end, so a uniform pick from it is
+        // a degree proportional pick
+        let mut repeated: Vec<usize> = Vec::new();
+        let mut targets: Vec<usize> = (0..m).collect();
+        for v in m..n {
+            targets.iter().for_each(|&t| {
+                g.add_link(&Link::new(
+                    v.to_string(),
+                    t.to_string(),
+                    "".to_string(),
+                    1.0,
+                ))
+                .unwrap();
+            });
+            repeated.extend(&targets);
+            repeated.extend(std::iter::repeat_n(v, m));
+            let mut picked = HashSet::new();
+            while picked.len() < m {
+                picked.insert(repeated[rng.gen_range(0..repeated.len())]);
+            }
+            targets = picked.into_iter().collect();
+            targets.sort_unstable();
+        }
+        g
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_grid_graph() {
+            let g = grid_graph(3, 4, false);
+            assert_eq!(g.nodes.len(), 12);
+            // 3 * 3 horizontal + 2 * 4 vertical
+            assert_eq!(g.links.len(), 17);
+            for corner in &["0_0", "0_3", "2_0", "2_3"] {
+                assert_eq!(g.degree_centrality(corner), 2);
+            }
+            assert_eq!(g.degree_centrality("1_1"), 4);
+            let g = grid_graph(2, 2, true);
+            assert_eq!(g.links.len(), 8);
+            assert!(g.get_link("1_1", "0_1").is_some());
+            assert_eq!(grid_graph(0, 5, false).nodes.len(), 0);
+        }
+
+        #[test]
+        fn test_barabasi_albert() {
+            let g = barabasi_albert(300, 2, 7);
+            assert_eq!(g.nodes.len(), 300);
+            assert_eq!(g.links.len(), 298 * 2);
+            assert_eq!(g, barabasi_albert(300, 2, 7));
+            let degrees: Vec<usize> = g.nodes.iter().map(|n| g.degree_centrality(&n.id)).collect();
+            let avg = degrees.iter().sum::<usize>() as f64 / degrees.len() as f64;
+            let max = *degrees.iter().max().unwrap();
+            // hubs are far above the mean while most nodes sit below it
+            assert!(max as f64 > 4.0 * avg);
+            assert!(degrees.iter().filter(|&&d| (d as f64) < avg).count() > 150);
+        }
+    }
+}
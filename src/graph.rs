@@ -1,20 +1,60 @@
 pub mod graph {
+    use std::collections::hash_map::Entry;
     use std::collections::HashMap;
     extern crate rayon;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
     use rayon::prelude::*;
+    #[cfg(feature = "serde")]
+    use serde::{Deserialize, Serialize};
     use std::collections::HashSet;
+    #[cfg(feature = "serde")]
+    use std::convert::TryFrom;
+    use std::error::Error;
+    use std::fmt;
     use std::hash::Hash;
     use std::hash::Hasher;
+    use std::io::{BufRead, BufReader, Read};
+    use std::iter::FromIterator;
 
-    #[derive(Debug, Eq, Clone)]
+    // attribute key holding the display color of a node or link, e.g. "red"
+    // or "#ff0000". `to_dot` is the only exporter so far and writes it as
+    // graphviz's own `color=` property
+    pub const COLOR_ATTR: &str = "color";
+
+    #[derive(Debug, Eq, Clone, Default)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct Node {
         pub id: String,
         pub name: String,
+        // user defined metadata, not considered by equality and hashing
+        pub attributes: HashMap<String, String>,
     }
 
     impl Node {
         pub fn new(id: String, name: String) -> Self {
-            Node { id: id, name: name }
+            Node {
+                id,
+                name,
+                attributes: HashMap::new(),
+            }
+        }
+
+        pub fn get_attr(&self, key: &str) -> Option<&String> {
+            self.attributes.get(key)
+        }
+
+        // returns the previous value of the attribute if there was one
+        pub fn set_attr(&mut self, key: &str, value: &str) -> Option<String> {
+            self.attributes.insert(key.to_string(), value.to_string())
+        }
+
+        pub fn color(&self) -> Option<&String> {
+            self.get_attr(COLOR_ATTR)
+        }
+
+        pub fn set_color(&mut self, color: &str) -> Option<String> {
+            self.set_attr(COLOR_ATTR, color)
         }
     }
 
@@ -29,31 +69,230 @@ pub mod graph {
         }
     }
 
-    #[derive(Debug, Clone)]
+    #[derive(Debug, Clone, Default, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
     pub struct Link {
         pub source: String,
         pub target: String,
         pub label: String,
         pub weight: f64,
+        // user defined metadata, e.g. edge type or timestamp
+        pub attributes: HashMap<String, String>,
+    }
+
+    impl Link {
+        pub fn new(source: String, target: String, label: String, weight: f64) -> Self {
+            Link {
+                source,
+                target,
+                label,
+                weight,
+                attributes: HashMap::new(),
+            }
+        }
+
+        pub fn get_attr(&self, key: &str) -> Option<&String> {
+            self.attributes.get(key)
+        }
+
+        // returns the previous value of the attribute if there was one
+        pub fn set_attr(&mut self, key: &str, value: &str) -> Option<String> {
+            self.attributes.insert(key.to_string(), value.to_string())
+        }
+
+        pub fn color(&self) -> Option<&String> {
+            self.get_attr(COLOR_ATTR)
+        }
+
+        pub fn set_color(&mut self, color: &str) -> Option<String> {
+            self.set_attr(COLOR_ATTR, color)
+        }
     }
 
     fn make_link_key(source: &str, target: &str) -> String {
-        format!("{}_{}", &source, &target).to_string()
+        format!("{}_{}", source, target)
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum GraphError {
+        // node id
+        NodeExists(String),
+        // source and target id
+        LinkExists(String, String),
+        // node id
+        NodeNotFound(String),
+        // source and target id, rejected while `enforce_acyclic` is on
+        CreatesCycle(String, String),
+    }
+
+    impl fmt::Display for GraphError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                GraphError::NodeExists(id) => {
+                    write!(f, "[WARN] node{} is already existed, skipping", id)
+                }
+                GraphError::LinkExists(source, target) => write!(
+                    f,
+                    "[WARN] link {} to {} is already existed, skipping",
+                    source, target
+                ),
+                GraphError::NodeNotFound(id) => write!(f, "[ERROR] node {} is not found", id),
+                GraphError::CreatesCycle(source, target) => write!(
+                    f,
+                    "[ERROR] link {} to {} would create a cycle",
+                    source, target
+                ),
+            }
+        }
+    }
+
+    impl Error for GraphError {}
+
+    impl From<GraphError> for String {
+        fn from(e: GraphError) -> Self {
+            e.to_string()
+        }
+    }
+
+    // duplicates are reported through `log` when the `logging` feature is on,
+    // so bulk importers can silence them by log level
+    fn warn_duplicate(e: GraphError) -> GraphError {
+        #[cfg(feature = "logging")]
+        log::warn!("{}", e);
+        e
     }
 
-    #[derive(Debug)]
+    #[derive(Debug, Clone, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+    #[cfg_attr(feature = "serde", serde(try_from = "RawGraph"))]
     pub struct Graph {
         pub nodes: Vec<Node>,
-        // node id to nodes index
+        // node id to nodes index, derived from `nodes` so it is not serialized
+        #[cfg_attr(feature = "serde", serde(skip))]
         pub nodes_map: HashMap<String, usize>,
         pub links: HashMap<String, Link>,
-        // if it's a directed graph, default is true
+        // if it's a directed graph, default is true. the link keys depend on
+        // it, so once links were added change it through `set_directed`
         pub directed: bool,
         pub weighted: bool,
+        // in and out degree per node index, kept up to date by the mutating
+        // methods so degree queries do not have to build the matrix
+        #[cfg_attr(feature = "serde", serde(skip))]
+        in_degrees: Vec<usize>,
+        #[cfg_attr(feature = "serde", serde(skip))]
+        out_degrees: Vec<usize>,
+        // when on, add_link rejects links that would close a cycle, see
+        // `set_enforce_acyclic`
+        #[cfg_attr(feature = "serde", serde(default))]
+        enforce_acyclic: bool,
+    }
+
+    // serialized fields of a graph, deserialization goes through it so the
+    // skipped indexes are rebuilt and broken links are rejected
+    #[cfg(feature = "serde")]
+    #[derive(Deserialize)]
+    struct RawGraph {
+        nodes: Vec<Node>,
+        links: HashMap<String, Link>,
+        directed: bool,
+        weighted: bool,
+        #[serde(default)]
+        enforce_acyclic: bool,
+    }
+
+    #[cfg(feature = "serde")]
+    impl TryFrom<RawGraph> for Graph {
+        type Error = GraphError;
+
+        fn try_from(raw: RawGraph) -> Result<Self, Self::Error> {
+            let mut g = Graph {
+                nodes: raw.nodes,
+                nodes_map: HashMap::new(),
+                links: raw.links,
+                directed: raw.directed,
+                weighted: raw.weighted,
+                in_degrees: Vec::new(),
+                out_degrees: Vec::new(),
+                enforce_acyclic: raw.enforce_acyclic,
+            };
+            let mut ids = HashSet::new();
+            if let Some(n) = g.nodes.iter().find(|n| !ids.insert(n.id.as_str())) {
+                return Err(GraphError::NodeExists(n.id.clone()));
+            }
+            if let Some(id) = g
+                .links
+                .values()
+                .flat_map(|l| [&l.source, &l.target])
+                .find(|id| !ids.contains(id.as_str()))
+            {
+                return Err(GraphError::NodeNotFound(id.clone()));
+            }
+            g.rebuild_indexes();
+            Ok(g)
+        }
+    }
+
+    // whether bit `idx` is set in a row of `to_bitset_matrix`
+    pub fn bitset_contains(row: &[u64], idx: usize) -> bool {
+        row[idx / 64] >> (idx % 64) & 1 == 1
+    }
+
+    // saved node, link and flag state of a graph, see `Graph::snapshot`.
+    // `nodes_map` is not stored, it is rebuilt on restore
+    #[derive(Debug, Clone)]
+    pub struct GraphSnapshot {
+        nodes: Vec<Node>,
+        links: HashMap<String, Link>,
+        directed: bool,
+        weighted: bool,
+    }
+
+    impl Default for Graph {
+        fn default() -> Self {
+            Graph::new()
+        }
+    }
+
+    // builds a directed graph from links, duplicated links are skipped
+    impl FromIterator<Link> for Graph {
+        fn from_iter<I: IntoIterator<Item = Link>>(iter: I) -> Self {
+            let mut g = Graph::new();
+            iter.into_iter().for_each(|l| {
+                let _ = g.add_link(&l);
+            });
+            g
+        }
     }
 
     // Graph construct related methods
     impl Graph {
+        // key of the link in `links`, the endpoints are sorted for undirected
+        // graphs so a-b and b-a refer to the same link
+        pub fn link_key(&self, source: &str, target: &str) -> String {
+            if !self.directed && target < source {
+                make_link_key(target, source)
+            } else {
+                make_link_key(source, target)
+            }
+        }
+
+        // switches the graph between directed and undirected and re-keys the
+        // links to match. when going undirected a-b and b-a collapse into
+        // one link, the one with the smaller (source, target) is kept
+        pub fn set_directed(&mut self, directed: bool) {
+            if self.directed == directed {
+                return;
+            }
+            self.directed = directed;
+            let mut links: Vec<Link> = std::mem::take(&mut self.links).into_values().collect();
+            links.sort_by(|a, b| (&a.source, &a.target).cmp(&(&b.source, &b.target)));
+            for l in links {
+                let key = self.link_key(&l.source, &l.target);
+                self.links.entry(key).or_insert(l);
+            }
+            self.rebuild_indexes();
+        }
+
         pub fn new() -> Self {
             Graph {
                 nodes_map: HashMap::new(),
@@ -61,410 +300,4281 @@ pub mod graph {
                 nodes: Vec::new(),
                 directed: true,
                 weighted: false,
+                in_degrees: Vec::new(),
+                out_degrees: Vec::new(),
+                enforce_acyclic: false,
+            }
+        }
+
+        // recomputes `nodes_map` and the degree counters from nodes and links
+        fn rebuild_indexes(&mut self) {
+            self.nodes_map = self
+                .nodes
+                .iter()
+                .enumerate()
+                .map(|(i, n)| (n.id.clone(), i))
+                .collect();
+            self.in_degrees = vec![0; self.nodes.len()];
+            self.out_degrees = vec![0; self.nodes.len()];
+            for l in self.links.values() {
+                self.out_degrees[self.nodes_map[&l.source]] += 1;
+                self.in_degrees[self.nodes_map[&l.target]] += 1;
             }
         }
+
         // TODO: replace node
-        pub fn add_node(&mut self, n: &Node) -> Result<bool, String> {
+        pub fn add_node(&mut self, n: &Node) -> Result<bool, GraphError> {
             // 如果节点已经存在，则不插入
             if self.nodes_map.contains_key(&n.id) {
-                return Err(format!("[WARN] node{} is already existed, skipping", &n.id));
+                return Err(warn_duplicate(GraphError::NodeExists(n.id.clone())));
             }
             self.nodes_map.insert(n.id.clone(), self.nodes.len());
             self.nodes.push(n.clone());
+            self.in_degrees.push(0);
+            self.out_degrees.push(0);
             Ok(true)
         }
         // TODO: replace link
-        pub fn add_link(&mut self, l: &Link) -> Result<bool, String> {
+        pub fn add_link(&mut self, l: &Link) -> Result<bool, GraphError> {
+            if self.enforce_acyclic
+                && !self
+                    .links
+                    .contains_key(&self.link_key(&l.source, &l.target))
+                && self.would_create_cycle(&l.source, &l.target)
+            {
+                return Err(GraphError::CreatesCycle(l.source.clone(), l.target.clone()));
+            }
             if !self.nodes_map.contains_key(&l.source) {
-                self.add_node(&Node {
-                    id: l.source.clone(),
-                    name: "".to_string(),
-                })?;
+                self.add_node(&Node::new(l.source.clone(), "".to_string()))?;
             }
             if !self.nodes_map.contains_key(&l.target) {
-                self.add_node(&Node {
-                    id: l.target.clone(),
-                    name: "".to_string(),
-                })?;
+                self.add_node(&Node::new(l.target.clone(), "".to_string()))?;
             }
-            let key = make_link_key(&l.source, &l.target);
-            if self.links.contains_key(&key) {
-                Err(format!(
-                    "[WARN] link {} to {} is already existed, skipping",
-                    l.source, l.target
-                ))
-            } else {
-                self.links.insert(key, l.clone());
-                Ok(true)
+            let key = self.link_key(&l.source, &l.target);
+            match self.links.entry(key) {
+                Entry::Occupied(_) => Err(warn_duplicate(GraphError::LinkExists(
+                    l.source.clone(),
+                    l.target.clone(),
+                ))),
+                Entry::Vacant(e) => {
+                    e.insert(l.clone());
+                    self.out_degrees[self.nodes_map[&l.source]] += 1;
+                    self.in_degrees[self.nodes_map[&l.target]] += 1;
+                    Ok(true)
+                }
             }
         }
-    }
 
-    // Graph queries works both on directed graph and undirected graph
-    impl Graph {
-        pub fn to_matrix(&self) -> Vec<Vec<bool>> {
-            self.links.iter().fold(
-                vec![vec![false; self.nodes.len()]; self.nodes.len()],
-                |mut rows, (_, l)| {
-                    rows[self.nodes_map[&l.source]][self.nodes_map[&l.target]] = true;
-                    if !self.directed {
-                        rows[self.nodes_map[&l.target]][self.nodes_map[&l.source]] = true;
+        // whether a link source -> target would close a cycle: it is a self
+        // loop or source can already be reached from target
+        fn would_create_cycle(&self, source: &str, target: &str) -> bool {
+            if source == target {
+                return true;
+            }
+            let (s, t) = match (self.nodes_map.get(source), self.nodes_map.get(target)) {
+                (Some(&s), Some(&t)) => (s, t),
+                _ => return false,
+            };
+            let mut adj: Vec<Vec<usize>> = vec![Vec::new(); self.nodes.len()];
+            self.links.values().for_each(|l| {
+                let (u, v) = (self.nodes_map[&l.source], self.nodes_map[&l.target]);
+                adj[u].push(v);
+                if !self.directed {
+                    adj[v].push(u);
+                }
+            });
+            let mut seen = vec![false; adj.len()];
+            let mut stack = vec![t];
+            seen[t] = true;
+            while let Some(u) = stack.pop() {
+                if u == s {
+                    return true;
+                }
+                for &v in &adj[u] {
+                    if !seen[v] {
+                        seen[v] = true;
+                        stack.push(v);
                     }
-                    rows
-                },
-            )
+                }
+            }
+            false
         }
 
-        pub fn direct_connected(&self, source_id: &str) -> Vec<Node> {
-            if !self.nodes_map.contains_key(source_id) {
-                return Vec::new();
-            }
-            let m = self.to_matrix();
-            let source_idx = self.nodes_map[source_id];
-            m[source_idx]
-                .par_iter()
-                .enumerate()
-                .filter_map(|(idx, &is_connected)| {
-                    if is_connected {
-                        Some(self.nodes[idx].clone())
-                    } else {
-                        None
+        // no directed cycle, or no cycle at all for undirected graphs. self
+        // loops count as cycles
+        fn is_acyclic(&self) -> bool {
+            let n = self.nodes.len();
+            if self.directed {
+                let mut indegree = vec![0; n];
+                let mut adj: Vec<Vec<usize>> = vec![Vec::new(); n];
+                self.links.values().for_each(|l| {
+                    let (u, v) = (self.nodes_map[&l.source], self.nodes_map[&l.target]);
+                    adj[u].push(v);
+                    indegree[v] += 1;
+                });
+                let mut stack: Vec<usize> = (0..n).filter(|&u| indegree[u] == 0).collect();
+                let mut visited = 0;
+                while let Some(u) = stack.pop() {
+                    visited += 1;
+                    for &v in &adj[u] {
+                        indegree[v] -= 1;
+                        if indegree[v] == 0 {
+                            stack.push(v);
+                        }
+                    }
+                }
+                visited == n
+            } else {
+                // union find, a link joining an already connected pair closes
+                // a cycle
+                let mut parent: Vec<usize> = (0..n).collect();
+                fn find(parent: &mut [usize], mut u: usize) -> usize {
+                    while parent[u] != u {
+                        parent[u] = parent[parent[u]];
+                        u = parent[u];
                     }
+                    u
+                }
+                self.links.values().all(|l| {
+                    let a = find(&mut parent, self.nodes_map[&l.source]);
+                    let b = find(&mut parent, self.nodes_map[&l.target]);
+                    parent[a] = b;
+                    a != b
                 })
-                .collect::<Vec<Node>>()
+            }
         }
 
-        pub fn get_node(&self, id: &str) -> Option<Node> {
-            if self.nodes_map.contains_key(id) {
-                Some(self.nodes[self.nodes_map[id]].clone())
-            } else {
-                None
+        // turns cycle prevention in add_link on or off, e.g. for dependency
+        // graphs that must stay a DAG. turning it on fails when the graph
+        // already has a cycle
+        pub fn set_enforce_acyclic(&mut self, enforce: bool) -> Result<(), String> {
+            if enforce && !self.is_acyclic() {
+                return Err("[ERROR] graph already has a cycle".to_string());
             }
+            self.enforce_acyclic = enforce;
+            Ok(())
         }
 
-        pub fn get_link(&self, source: &str, target: &str) -> Option<Link> {
-            let key = &make_link_key(source, target);
-            if self.links.contains_key(key) {
-                Some(self.links[key].clone())
-            } else {
-                None
+        pub fn enforce_acyclic(&self) -> bool {
+            self.enforce_acyclic
+        }
+
+        // removes the link between source and target and returns it, on
+        // undirected graphs the endpoints may be given in either order
+        pub fn remove_link(&mut self, source: &str, target: &str) -> Option<Link> {
+            let l = self.links.remove(&self.link_key(source, target))?;
+            self.out_degrees[self.nodes_map[&l.source]] -= 1;
+            self.in_degrees[self.nodes_map[&l.target]] -= 1;
+            Some(l)
+        }
+
+        // drops all nodes and links but keeps the flags and the allocated
+        // capacity, so the graph can be reused
+        pub fn clear(&mut self) {
+            self.nodes.clear();
+            self.nodes_map.clear();
+            self.links.clear();
+            self.in_degrees.clear();
+            self.out_degrees.clear();
+        }
+
+        // makes room for at least the given number of extra nodes and links,
+        // e.g. before a bulk import of known size
+        pub fn reserve(&mut self, additional_nodes: usize, additional_links: usize) {
+            self.nodes.reserve(additional_nodes);
+            self.nodes_map.reserve(additional_nodes);
+            self.in_degrees.reserve(additional_nodes);
+            self.out_degrees.reserve(additional_nodes);
+            self.links.reserve(additional_links);
+        }
+
+        // gives back unused capacity, e.g. after removing many nodes or links
+        pub fn shrink_to_fit(&mut self) {
+            self.nodes.shrink_to_fit();
+            self.nodes_map.shrink_to_fit();
+            self.in_degrees.shrink_to_fit();
+            self.out_degrees.shrink_to_fit();
+            self.links.shrink_to_fit();
+        }
+
+        // drops all links, nodes are kept
+        pub fn clear_edges(&mut self) {
+            self.links.clear();
+            self.in_degrees.iter_mut().for_each(|d| *d = 0);
+            self.out_degrees.iter_mut().for_each(|d| *d = 0);
+        }
+
+        // captures the current state so it can be brought back with `restore`,
+        // e.g. to implement undo in an editor
+        pub fn snapshot(&self) -> GraphSnapshot {
+            GraphSnapshot {
+                nodes: self.nodes.clone(),
+                links: self.links.clone(),
+                directed: self.directed,
+                weighted: self.weighted,
             }
         }
 
-        // finds all connected components in a graph
-        // graph must be an undirected graph, otherwise it will panic
-        pub fn connected_components(&mut self) -> Vec<Vec<Node>> {
-            // set directed state, set back when done
-            let cur_directed = self.directed;
-            self.directed = false;
-            if self.nodes.len() == 0 {
-                self.directed = cur_directed;
-                return Vec::new();
+        pub fn restore(&mut self, snapshot: GraphSnapshot) {
+            self.nodes = snapshot.nodes;
+            self.links = snapshot.links;
+            self.directed = snapshot.directed;
+            self.weighted = snapshot.weighted;
+            self.rebuild_indexes();
+        }
+
+        // drops every self loop and returns how many were removed
+        pub fn remove_self_loops(&mut self) -> usize {
+            let before = self.links.len();
+            self.links.retain(|_, l| l.source != l.target);
+            self.rebuild_indexes();
+            before - self.links.len()
+        }
+
+        // in place version of `filter_edges_by_weight`: drops the links with
+        // weight < min_weight, keeps every node and returns how many links
+        // were removed
+        pub fn prune_edges(&mut self, min_weight: f64) -> usize {
+            let before = self.links.len();
+            self.links.retain(|_, l| l.weight >= min_weight);
+            self.rebuild_indexes();
+            before - self.links.len()
+        }
+
+        // keeps only the nodes for which pred is true, like `Vec::retain`.
+        // links touching a removed node go with it, the index and degree
+        // counters are rebuilt once. returns how many nodes were removed
+        pub fn retain_nodes<F: Fn(&Node) -> bool>(&mut self, pred: F) -> usize {
+            let before = self.nodes.len();
+            self.nodes.retain(|n| pred(n));
+            if self.nodes.len() == before {
+                return 0;
             }
-            let mut openset: HashSet<Node> = self.nodes.iter().map(|n| n.clone()).collect();
-            let mut components: HashMap<String, Vec<Node>> = HashMap::new();
-            let mut start_node = self.nodes[0].clone();
-            loop {
-                openset.remove(&start_node);
-                components.insert(start_node.id.clone(), vec![start_node.clone()]);
-                loop {
-                    let mut direct_connected_nodes: Vec<Node> = self
-                        .direct_connected(&start_node.id)
-                        .iter()
-                        .filter(|n| openset.contains(&n))
-                        .cloned()
-                        .collect();
-                    if direct_connected_nodes.len() > 0 {
-                        direct_connected_nodes.iter().for_each(|n| {
-                            openset.remove(&n);
-                        });
-                        components
-                            .get_mut(&start_node.id)
-                            .unwrap()
-                            .append(&mut direct_connected_nodes);
-                    } else {
-                        break;
-                    }
+            let kept: HashSet<&str> = self.nodes.iter().map(|n| n.id.as_str()).collect();
+            self.links.retain(|_, l| {
+                kept.contains(l.source.as_str()) && kept.contains(l.target.as_str())
+            });
+            self.rebuild_indexes();
+            before - self.nodes.len()
+        }
+
+        // like add_link, but an existing link gets the new weight added to its
+        // own instead of being rejected, which also marks the graph weighted.
+        // fails only when a new link is rejected by `enforce_acyclic`
+        pub fn add_or_accumulate_link(&mut self, l: &Link) -> Result<(), GraphError> {
+            let key = self.link_key(&l.source, &l.target);
+            match self.links.get_mut(&key) {
+                Some(existing) => {
+                    existing.weight += l.weight;
+                    self.weighted = true;
                 }
-                if let Some(n) = openset.iter().next() {
-                    start_node = n.clone();
-                } else {
-                    break;
+                None => {
+                    self.add_link(l)?;
                 }
             }
-            self.directed = cur_directed;
-            components
-                .iter()
-                .map(|(_, component)| component)
-                .cloned()
-                .collect()
+            Ok(())
         }
-    }
 
-    // Graph queries only works on directed graph
-    impl Graph {
-        pub fn indegree(&mut self, node_id: &str) -> usize {
-            if !self.nodes_map.contains_key(node_id) {
-                return 0;
-            }
-            let node_idx = self.nodes_map[node_id];
-            // set directed state, set back when done
-            let cur_directed = self.directed;
-            if !self.directed {
-                self.directed = true;
-            }
-            let m = self.to_matrix();
-            self.directed = cur_directed;
-            m.iter().fold(0, |mut indegree, row| {
-                if row[node_idx] {
-                    indegree += 1;
+        // rescales the outgoing link weights of every node so they sum to 1.0,
+        // turning the graph into a transition matrix, and marks it weighted.
+        // links count as outgoing from their source, also on undirected
+        // graphs. nodes without out links or with a zero total are left as is
+        pub fn normalize_out_weights(&mut self) {
+            let mut totals: HashMap<String, f64> = HashMap::new();
+            self.links
+                .values()
+                .for_each(|l| *totals.entry(l.source.clone()).or_insert(0.0) += l.weight);
+            self.links.values_mut().for_each(|l| {
+                let total = totals[&l.source];
+                if total != 0.0 {
+                    l.weight /= total;
                 }
-                indegree
-            })
+            });
+            self.weighted = true;
         }
 
-        pub fn outdegree(&mut self, node_id: &str) -> usize {
-            if !self.nodes_map.contains_key(node_id) {
-                return 0;
+        // folds `merge_id` into `keep_id`: its links are moved over, parallel
+        // links get their weights accumulated and self loops created by the
+        // merge are dropped, then `merge_id` is removed. the `weighted` flag
+        // is left as is.
+        // while `enforce_acyclic` is on, a merge that would close a cycle is
+        // undone and reported
+        pub fn merge_nodes(&mut self, keep_id: &str, merge_id: &str) -> Result<(), GraphError> {
+            for id in [keep_id, merge_id].iter() {
+                if !self.nodes_map.contains_key(*id) {
+                    return Err(GraphError::NodeNotFound(id.to_string()));
+                }
             }
-            let node_idx = self.nodes_map[node_id];
-            // set directed state, set back when done
-            let cur_directed = self.directed;
-            if !self.directed {
-                self.directed = true;
+            if keep_id == merge_id {
+                return Ok(());
             }
-            let m = self.to_matrix();
-            self.directed = cur_directed;
-            m[node_idx]
+            let before = if self.enforce_acyclic {
+                Some(self.snapshot())
+            } else {
+                None
+            };
+            let keys: Vec<String> = self
+                .links
                 .iter()
-                .filter(|&&is_connected| is_connected)
-                .cloned()
-                .collect::<Vec<bool>>()
-                .len()
+                .filter(|(_, l)| l.source == merge_id || l.target == merge_id)
+                .map(|(k, _)| k.clone())
+                .collect();
+            let mut moved: Vec<Link> = keys
+                .iter()
+                .filter_map(|k| {
+                    let l = self.links.remove(k)?;
+                    self.out_degrees[self.nodes_map[&l.source]] -= 1;
+                    self.in_degrees[self.nodes_map[&l.target]] -= 1;
+                    Some(l)
+                })
+                .collect();
+            moved.sort_by(|a, b| (&a.source, &a.target).cmp(&(&b.source, &b.target)));
+            for mut l in moved {
+                if l.source == merge_id {
+                    l.source = keep_id.to_string();
+                }
+                if l.target == merge_id {
+                    l.target = keep_id.to_string();
+                }
+                if l.source == l.target {
+                    continue;
+                }
+                let key = self.link_key(&l.source, &l.target);
+                if let Some(existing) = self.links.get_mut(&key) {
+                    existing.weight += l.weight;
+                } else if let Err(e) = self.add_link(&l) {
+                    self.restore(before.unwrap());
+                    return Err(e);
+                }
+            }
+            let idx = self.nodes_map[merge_id];
+            self.nodes.remove(idx);
+            self.rebuild_indexes();
+            Ok(())
         }
+    }
 
-        pub fn degree_centrality(&mut self, node_id: &str) -> usize {
-            self.indegree(node_id) + self.outdegree(node_id)
+    // quotes a CSV field when it contains a comma, quote or line break,
+    // doubling the quotes inside
+    fn csv_field(s: &str) -> String {
+        if s.contains(&[',', '"', '\n', '\r'][..]) {
+            format!("\"{}\"", s.replace('"', "\"\""))
+        } else {
+            s.to_string()
         }
+    }
 
-        pub fn pagerank_centrality(&self, node_id: &str) -> f64 {
-            0.0
+    fn dot_quote(s: &str) -> String {
+        format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+
+    // formats user attributes as extra `, "key"="value"` pairs sorted by key,
+    // the color attribute comes first as graphviz's own `color=` property
+    fn dot_attributes(attributes: &HashMap<String, String>) -> String {
+        let color = attributes
+            .get(COLOR_ATTR)
+            .map(|c| format!(", {}={}", COLOR_ATTR, dot_quote(c)))
+            .unwrap_or_default();
+        let mut keys: Vec<&String> = attributes.keys().filter(|k| *k != COLOR_ATTR).collect();
+        keys.sort();
+        color
+            + &keys
+                .iter()
+                .map(|k| format!(", {}={}", dot_quote(k), dot_quote(&attributes[*k])))
+                .collect::<String>()
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum DotToken {
+        Id(String),
+        LBrace,
+        RBrace,
+        LBracket,
+        RBracket,
+        Semi,
+        Comma,
+        Equal,
+        Arrow,
+        Dash,
+    }
+
+    // splits a DOT source into tokens, each tagged with its (line, column)
+    fn dot_tokenize(src: &str) -> Result<Vec<(DotToken, usize, usize)>, String> {
+        let mut tokens = Vec::new();
+        let mut chars = src.chars().peekable();
+        let (mut line, mut col) = (1, 1);
+        while let Some(&c) = chars.peek() {
+            let (tok_line, tok_col) = (line, col);
+            let mut bump = |chars: &mut std::iter::Peekable<std::str::Chars>| {
+                let c = chars.next();
+                if c == Some('\n') {
+                    line += 1;
+                    col = 1;
+                } else {
+                    col += 1;
+                }
+                c
+            };
+            if c.is_whitespace() {
+                bump(&mut chars);
+                continue;
+            }
+            let token = match c {
+                '{' | '}' | '[' | ']' | ';' | ',' | '=' => {
+                    bump(&mut chars);
+                    match c {
+                        '{' => DotToken::LBrace,
+                        '}' => DotToken::RBrace,
+                        '[' => DotToken::LBracket,
+                        ']' => DotToken::RBracket,
+                        ';' => DotToken::Semi,
+                        ',' => DotToken::Comma,
+                        _ => DotToken::Equal,
+                    }
+                }
+                '#' => {
+                    // preprocessor style line comment
+                    while chars.peek().is_some() && chars.peek() != Some(&'\n') {
+                        bump(&mut chars);
+                    }
+                    continue;
+                }
+                '/' => {
+                    bump(&mut chars);
+                    match bump(&mut chars) {
+                        Some('/') => {
+                            while chars.peek().is_some() && chars.peek() != Some(&'\n') {
+                                bump(&mut chars);
+                            }
+                        }
+                        Some('*') => {
+                            let mut prev = ' ';
+                            loop {
+                                match bump(&mut chars) {
+                                    Some('/') if prev == '*' => break,
+                                    Some(c) => prev = c,
+                                    None => {
+                                        return Err(format!(
+                                            "[ERROR] unterminated comment at line {}, column {}",
+                                            tok_line, tok_col
+                                        ))
+                                    }
+                                }
+                            }
+                        }
+                        _ => {
+                            return Err(format!(
+                                "[ERROR] unexpected '/' at line {}, column {}",
+                                tok_line, tok_col
+                            ))
+                        }
+                    }
+                    continue;
+                }
+                '"' => {
+                    bump(&mut chars);
+                    let mut s = String::new();
+                    loop {
+                        match bump(&mut chars) {
+                            Some('"') => break,
+                            Some('\\') => match bump(&mut chars) {
+                                Some('"') => s.push('"'),
+                                Some('\\') => s.push('\\'),
+                                Some(c) => {
+                                    s.push('\\');
+                                    s.push(c);
+                                }
+                                None => break,
+                            },
+                            Some(c) => s.push(c),
+                            None => {
+                                return Err(format!(
+                                    "[ERROR] unterminated string at line {}, column {}",
+                                    tok_line, tok_col
+                                ))
+                            }
+                        }
+                    }
+                    DotToken::Id(s)
+                }
+                '-' if {
+                    let mut ahead = chars.clone();
+                    ahead.next();
+                    matches!(ahead.peek(), Some('>') | Some('-'))
+                } =>
+                {
+                    bump(&mut chars);
+                    match bump(&mut chars) {
+                        Some('>') => DotToken::Arrow,
+                        _ => DotToken::Dash,
+                    }
+                }
+                c if c.is_alphanumeric() || c == '_' || c == '.' || c == '-' => {
+                    let mut s = String::new();
+                    while let Some(&c) = chars.peek() {
+                        if c.is_alphanumeric() || c == '_' || c == '.' || c == '-' {
+                            // an edge operator ends the identifier
+                            let mut ahead = chars.clone();
+                            ahead.next();
+                            if c == '-' && matches!(ahead.peek(), Some('>') | Some('-')) {
+                                break;
+                            }
+                            s.push(c);
+                            bump(&mut chars);
+                        } else {
+                            break;
+                        }
+                    }
+                    DotToken::Id(s)
+                }
+                _ => {
+                    return Err(format!(
+                        "[ERROR] unexpected character '{}' at line {}, column {}",
+                        c, tok_line, tok_col
+                    ))
+                }
+            };
+            tokens.push((token, tok_line, tok_col));
         }
+        Ok(tokens)
     }
 
-    #[cfg(test)]
-    mod tests {
-        // Note this useful idiom: importing names from outer (for mod tests) scope.
-        use super::*;
+    // key, value and the line and column of the value token
+    type DotAttr = (String, String, (usize, usize));
 
-        fn help_create_test_directed_graph() -> Graph {
-            let mut g = Graph::new();
-            let n1 = Node::new("a".to_string(), "a".to_string());
-            let n2 = Node::new("b".to_string(), "b".to_string());
-            let n3 = Node::new("c".to_string(), "c".to_string());
-            let n4 = Node::new("d".to_string(), "d".to_string());
-            g.add_node(&n1);
-            g.add_node(&n2);
-            g.add_node(&n3);
-            g.add_node(&n4);
-            g.add_link(&Link {
-                source: "a".to_string(),
-                target: "b".to_string(),
-                label: "".to_string(),
-                weight: 1.0,
-            });
-            g.add_link(&Link {
-                source: "c".to_string(),
-                target: "d".to_string(),
-                label: "".to_string(),
-                weight: 1.0,
-            });
-            g
+    struct DotParser {
+        tokens: Vec<(DotToken, usize, usize)>,
+        pos: usize,
+    }
+
+    impl DotParser {
+        fn peek(&self) -> Option<&DotToken> {
+            self.tokens.get(self.pos).map(|(t, _, _)| t)
         }
 
-        #[test]
-        fn test_create_graph() {
-            let g = Graph::new();
-            assert_eq!(g.weighted, false);
-            assert_eq!(g.directed, true);
-            assert_eq!(g.nodes.len(), 0);
-            assert_eq!(g.nodes_map.len(), 0);
-            assert_eq!(g.links.len(), 0);
+        fn error(&self, expected: &str) -> String {
+            match self.tokens.get(self.pos) {
+                Some((t, line, col)) => format!(
+                    "[ERROR] expected {}, found {:?} at line {}, column {}",
+                    expected, t, line, col
+                ),
+                None => format!("[ERROR] expected {}, found end of input", expected),
+            }
         }
 
-        #[test]
-        fn test_new_node() {
-            let n1 = Node::new("1".to_string(), "1".to_string());
-            let n2 = Node {
-                id: "1".to_string(),
+        fn expect(&mut self, token: DotToken, expected: &str) -> Result<(), String> {
+            if self.peek() == Some(&token) {
+                self.pos += 1;
+                Ok(())
+            } else {
+                Err(self.error(expected))
+            }
+        }
+
+        fn id(&mut self) -> Result<String, String> {
+            if let Some(DotToken::Id(s)) = self.peek() {
+                let s = s.clone();
+                self.pos += 1;
+                Ok(s)
+            } else {
+                Err(self.error("identifier"))
+            }
+        }
+
+        // parses zero or more `[k=v, ...]` lists into (key, value, position)
+        // triples, the position lets a value that does not parse be reported
+        fn attributes(&mut self) -> Result<Vec<DotAttr>, String> {
+            let mut attrs = Vec::new();
+            while self.peek() == Some(&DotToken::LBracket) {
+                self.pos += 1;
+                while self.peek() != Some(&DotToken::RBracket) {
+                    let key = self.id()?;
+                    self.expect(DotToken::Equal, "'='")?;
+                    let at = self
+                        .tokens
+                        .get(self.pos)
+                        .map_or((0, 0), |&(_, l, c)| (l, c));
+                    let value = self.id()?;
+                    attrs.push((key, value, at));
+                    if let Some(DotToken::Comma) | Some(DotToken::Semi) = self.peek() {
+                        self.pos += 1;
+                    }
+                }
+                self.pos += 1;
+            }
+            Ok(attrs)
+        }
+    }
+
+    // Graph import and export
+    impl Graph {
+        // exports the graph in Graphviz DOT format, links are sorted by key
+        // so the output is stable
+        pub fn to_dot(&self) -> String {
+            let (keyword, edge_op) = if self.directed {
+                ("digraph", "->")
+            } else {
+                ("graph", "--")
+            };
+            let mut dot = format!("{} {{\n", keyword);
+            self.nodes.iter().for_each(|n| {
+                dot.push_str(&format!(
+                    "    {} [label={}{}];\n",
+                    dot_quote(&n.id),
+                    dot_quote(&n.name),
+                    dot_attributes(&n.attributes)
+                ));
+            });
+            let mut keys: Vec<&String> = self.links.keys().collect();
+            keys.sort();
+            keys.iter().for_each(|k| {
+                let l = &self.links[*k];
+                dot.push_str(&format!(
+                    "    {} {} {} [label={}, weight={}{}];\n",
+                    dot_quote(&l.source),
+                    edge_op,
+                    dot_quote(&l.target),
+                    dot_quote(&l.label),
+                    l.weight,
+                    dot_attributes(&l.attributes)
+                ));
+            });
+            dot.push_str("}\n");
+            dot
+        }
+
+        // parses a subset of Graphviz DOT: `graph`/`digraph`, node statements
+        // and edge statements with optional `label` and `weight` attributes,
+        // other attributes and attribute statements are ignored
+        pub fn from_dot(src: &str) -> Result<Graph, String> {
+            let mut p = DotParser {
+                tokens: dot_tokenize(src)?,
+                pos: 0,
+            };
+            let mut g = Graph::new();
+            if p.peek() == Some(&DotToken::Id("strict".to_string())) {
+                p.pos += 1;
+            }
+            match p.id() {
+                Ok(ref k) if k == "digraph" => g.directed = true,
+                Ok(ref k) if k == "graph" => g.directed = false,
+                _ => {
+                    p.pos = p.pos.saturating_sub(1);
+                    return Err(p.error("'graph' or 'digraph'"));
+                }
+            }
+            if let Some(DotToken::Id(_)) = p.peek() {
+                p.pos += 1;
+            }
+            p.expect(DotToken::LBrace, "'{'")?;
+            while p.peek() != Some(&DotToken::RBrace) {
+                if p.peek() == Some(&DotToken::Semi) {
+                    p.pos += 1;
+                    continue;
+                }
+                let id = p.id()?;
+                if id == "node" || id == "edge" || id == "graph" {
+                    // attribute statement, defaults are not supported
+                    p.attributes()?;
+                    continue;
+                }
+                if p.peek() == Some(&DotToken::Equal) {
+                    // graph level `key=value`
+                    p.pos += 1;
+                    p.id()?;
+                    continue;
+                }
+                let mut chain = vec![id];
+                while let Some(op) = p.peek() {
+                    match (op, g.directed) {
+                        (DotToken::Arrow, true) | (DotToken::Dash, false) => {
+                            p.pos += 1;
+                            chain.push(p.id()?);
+                        }
+                        (DotToken::Arrow, false) => return Err(p.error("'--'")),
+                        (DotToken::Dash, true) => return Err(p.error("'->'")),
+                        _ => break,
+                    }
+                }
+                let attrs = p.attributes()?;
+                let label = attrs
+                    .iter()
+                    .find(|(k, _, _)| k == "label")
+                    .map(|(_, v, _)| v.clone());
+                if chain.len() == 1 {
+                    let id = chain.pop().unwrap();
+                    if !g.nodes_map.contains_key(&id) {
+                        g.add_node(&Node::new(id.clone(), "".to_string()))?;
+                    }
+                    let node = &mut g.nodes[g.nodes_map[&id]];
+                    if let Some(name) = label {
+                        node.name = name;
+                    }
+                    attrs
+                        .iter()
+                        .filter(|(k, _, _)| k != "label")
+                        .for_each(|(k, v, _)| {
+                            node.set_attr(k, v);
+                        });
+                    continue;
+                }
+                let weight = match attrs.iter().find(|(k, _, _)| k == "weight") {
+                    Some((_, v, (line, col))) => v.parse::<f64>().map_err(|_| {
+                        format!(
+                            "[ERROR] invalid weight {} at line {}, column {}",
+                            v, line, col
+                        )
+                    })?,
+                    None => 1.0,
+                };
+                let attributes: HashMap<String, String> = attrs
+                    .into_iter()
+                    .filter(|(k, _, _)| k != "label" && k != "weight")
+                    .map(|(k, v, _)| (k, v))
+                    .collect();
+                chain.windows(2).for_each(|pair| {
+                    // parallel edges can not be represented, keep the first one
+                    let _ = g.add_link(&Link {
+                        source: pair[0].clone(),
+                        target: pair[1].clone(),
+                        label: label.clone().unwrap_or_default(),
+                        weight,
+                        attributes: attributes.clone(),
+                    });
+                });
+            }
+            p.expect(DotToken::RBrace, "'}'")?;
+            if p.peek().is_some() {
+                return Err(p.error("end of input"));
+            }
+            Ok(g)
+        }
+
+        // (nodes CSV with `id,name`, links CSV with `source,target,label,weight`)
+        // as imported by Gephi and spreadsheets. nodes keep their order,
+        // links are sorted by key
+        pub fn to_csv_tables(&self) -> (String, String) {
+            let mut nodes = String::from("id,name\n");
+            self.nodes.iter().for_each(|n| {
+                nodes.push_str(&format!("{},{}\n", csv_field(&n.id), csv_field(&n.name)));
+            });
+            let mut links = String::from("source,target,label,weight\n");
+            let mut keys: Vec<&String> = self.links.keys().collect();
+            keys.sort();
+            keys.iter().for_each(|k| {
+                let l = &self.links[*k];
+                links.push_str(&format!(
+                    "{},{},{},{}\n",
+                    csv_field(&l.source),
+                    csv_field(&l.target),
+                    csv_field(&l.label),
+                    l.weight
+                ));
+            });
+            (nodes, links)
+        }
+
+        // node id => ids of its out neighbors in node order, every node gets
+        // an entry. undirected links are listed under both endpoints
+        pub fn to_adjacency_list(&self) -> HashMap<String, Vec<String>> {
+            self.nodes
+                .iter()
+                .map(|n| {
+                    let neighbors = self
+                        .neighbor_indices(&n.id)
+                        .into_iter()
+                        .map(|(idx, _)| self.nodes[idx].id.clone())
+                        .collect();
+                    (n.id.clone(), neighbors)
+                })
+                .collect()
+        }
+
+        // inverse of `to_adjacency_list`: a node for every key and neighbor
+        // and a link of weight 1.0 per listed neighbor. keys are added in
+        // sorted order so node positions do not depend on hashing, links
+        // listed twice (e.g. both ways on an undirected graph) are kept once
+        pub fn from_adjacency_list(adj: &HashMap<String, Vec<String>>, directed: bool) -> Graph {
+            let mut g = Graph::new();
+            g.directed = directed;
+            let mut keys: Vec<&String> = adj.keys().collect();
+            keys.sort();
+            keys.iter().for_each(|k| {
+                let _ = g.add_node(&Node::new(k.to_string(), "".to_string()));
+            });
+            keys.iter().for_each(|k| {
+                adj[*k].iter().for_each(|t| {
+                    let _ = g.add_link(&Link::new(k.to_string(), t.clone(), "".to_string(), 1.0));
+                });
+            });
+            g
+        }
+
+        // compressed sparse row form of the weighted adjacency matrix:
+        // (row pointers, column indices, values). row i spans
+        // `row_ptr[i]..row_ptr[i + 1]`, columns are sorted by node index and
+        // values are 1.0 for unweighted graphs
+        pub fn to_csr(&self) -> (Vec<usize>, Vec<usize>, Vec<f64>) {
+            let mut rows: Vec<Vec<(usize, f64)>> = vec![Vec::new(); self.nodes.len()];
+            self.links.values().for_each(|l| {
+                let (s, t) = (self.nodes_map[&l.source], self.nodes_map[&l.target]);
+                let w = if self.weighted { l.weight } else { 1.0 };
+                rows[s].push((t, w));
+                if !self.directed && s != t {
+                    rows[t].push((s, w));
+                }
+            });
+            let mut row_ptr = vec![0];
+            let (mut cols, mut values) = (Vec::new(), Vec::new());
+            rows.iter_mut().for_each(|row| {
+                row.sort_by_key(|&(c, _)| c);
+                row.iter().for_each(|&(c, w)| {
+                    cols.push(c);
+                    values.push(w);
+                });
+                row_ptr.push(cols.len());
+            });
+            (row_ptr, cols, values)
+        }
+
+        // the weighted adjacency matrix in MatrixMarket coordinate format,
+        // rows and columns are 1-based positions in `nodes`. undirected links
+        // are written both ways since the matrix is declared general
+        pub fn to_matrix_market(&self) -> String {
+            let (row_ptr, cols, values) = self.to_csr();
+            let mut out = String::from("%%MatrixMarket matrix coordinate real general\n");
+            out.push_str(&format!(
+                "{} {} {}\n",
+                self.nodes.len(),
+                self.nodes.len(),
+                cols.len()
+            ));
+            row_ptr.windows(2).enumerate().for_each(|(r, span)| {
+                (span[0]..span[1]).for_each(|k| {
+                    out.push_str(&format!("{} {} {}\n", r + 1, cols[k] + 1, values[k]));
+                });
+            });
+            out
+        }
+
+        // compact binary encoding of the graph
+        #[cfg(feature = "bincode")]
+        pub fn to_bytes(&self) -> Result<Vec<u8>, String> {
+            bincode::serialize(self).map_err(|e| format!("[ERROR] {}", e))
+        }
+
+        // decodes a graph written by `to_bytes`, `nodes_map` is rebuilt from
+        // the node order and links to unknown nodes are an error
+        #[cfg(feature = "bincode")]
+        pub fn from_bytes(data: &[u8]) -> Result<Graph, String> {
+            bincode::deserialize(data).map_err(|e| format!("[ERROR] {}", e))
+        }
+
+        // reads an edge list line by line and adds the links incrementally,
+        // each line is `source target [weight]` separated by whitespace or
+        // commas, blank lines and lines starting with `#` are skipped.
+        // returns how many links were added, duplicated links are not counted.
+        // the flags are applied to an empty graph, a non empty one must
+        // already have the same flags since its link keys depend on them
+        pub fn add_edges_from_reader<R: Read>(
+            &mut self,
+            reader: R,
+            directed: bool,
+            weighted: bool,
+        ) -> Result<usize, String> {
+            if self.nodes.is_empty() && self.links.is_empty() {
+                self.directed = directed;
+                self.weighted = weighted;
+            } else if self.directed != directed || self.weighted != weighted {
+                return Err(format!(
+                    "[ERROR] can not read a directed={} weighted={} edge list into a directed={} weighted={} graph",
+                    directed, weighted, self.directed, self.weighted
+                ));
+            }
+            let mut added = 0;
+            for (line_no, line) in BufReader::new(reader).lines().enumerate() {
+                let line = line.map_err(|e| format!("[ERROR] line {}: {}", line_no + 1, e))?;
+                let line = line.trim();
+                if line.is_empty() || line.starts_with('#') {
+                    continue;
+                }
+                let fields: Vec<&str> = line
+                    .split(|c: char| c == ',' || c.is_whitespace())
+                    .filter(|f| !f.is_empty())
+                    .collect();
+                if fields.len() < 2 {
+                    return Err(format!(
+                        "[ERROR] line {}: expected source and target",
+                        line_no + 1
+                    ));
+                }
+                let weight = match fields.get(2) {
+                    Some(w) if weighted => w.parse::<f64>().map_err(|_| {
+                        format!("[ERROR] line {}: invalid weight {}", line_no + 1, w)
+                    })?,
+                    _ => 1.0,
+                };
+                let l = Link::new(
+                    fields[0].to_string(),
+                    fields[1].to_string(),
+                    "".to_string(),
+                    weight,
+                );
+                if self.add_link(&l).is_ok() {
+                    added += 1;
+                }
+            }
+            Ok(added)
+        }
+    }
+
+    // backtracking search for structure preserving maps from the nodes of
+    // `pattern` into distinct nodes of `host`: every pattern link must have a
+    // host link between the mapped nodes. with `exact` the map must also be
+    // onto and reflect host links back, i.e. an isomorphism. candidates are
+    // pruned by in/out degree, pattern nodes with a non empty name only map
+    // to host nodes with the same name
+    struct StructureMatcher<'a> {
+        pattern: &'a Graph,
+        host: &'a Graph,
+        pattern_matrix: Vec<Vec<bool>>,
+        host_matrix: Vec<Vec<bool>>,
+        pattern_degrees: Vec<(usize, usize)>,
+        host_degrees: Vec<(usize, usize)>,
+        // pattern node indices, each one linked to an earlier one if possible
+        order: Vec<usize>,
+        exact: bool,
+        match_names: bool,
+        first_only: bool,
+        mapping: Vec<usize>,
+        used: Vec<bool>,
+        found: Vec<Vec<usize>>,
+    }
+
+    // (out degree, in degree) per row/column of an adjacency matrix
+    fn matrix_degrees(matrix: &[Vec<bool>]) -> Vec<(usize, usize)> {
+        (0..matrix.len())
+            .map(|i| {
+                let out = matrix[i].iter().filter(|&&b| b).count();
+                let inn = matrix.iter().filter(|row| row[i]).count();
+                (out, inn)
+            })
+            .collect()
+    }
+
+    impl<'a> StructureMatcher<'a> {
+        fn new(pattern: &'a Graph, host: &'a Graph, exact: bool, match_names: bool) -> Self {
+            let pattern_matrix = pattern.to_matrix();
+            let host_matrix = host.to_matrix();
+            let pattern_degrees = matrix_degrees(&pattern_matrix);
+            let host_degrees = matrix_degrees(&host_matrix);
+            let n = pattern.nodes.len();
+            let mut order: Vec<usize> = Vec::with_capacity(n);
+            let mut placed = vec![false; n];
+            while order.len() < n {
+                // most links into the placed nodes first, then highest degree
+                let next = (0..n)
+                    .filter(|&p| !placed[p])
+                    .max_by_key(|&p| {
+                        let linked = order
+                            .iter()
+                            .filter(|&&q| pattern_matrix[p][q] || pattern_matrix[q][p])
+                            .count();
+                        let (out, inn) = pattern_degrees[p];
+                        (linked, out + inn, std::cmp::Reverse(p))
+                    })
+                    .unwrap();
+                placed[next] = true;
+                order.push(next);
+            }
+            StructureMatcher {
+                pattern,
+                host,
+                pattern_matrix,
+                host_matrix,
+                pattern_degrees,
+                host_degrees,
+                order,
+                exact,
+                match_names,
+                first_only: false,
+                mapping: vec![usize::MAX; n],
+                used: vec![false; host.nodes.len()],
+                found: Vec::new(),
+            }
+        }
+
+        fn feasible(&self, p: usize, h: usize) -> bool {
+            let (po, pi) = self.pattern_degrees[p];
+            let (ho, hi) = self.host_degrees[h];
+            if (self.exact && (po, pi) != (ho, hi)) || po > ho || pi > hi {
+                return false;
+            }
+            let name = &self.pattern.nodes[p].name;
+            if self.match_names && !name.is_empty() && *name != self.host.nodes[h].name {
+                return false;
+            }
+            let (pm, hm) = (&self.pattern_matrix, &self.host_matrix);
+            if pm[p][p] != hm[h][h] && (pm[p][p] || self.exact) {
+                return false;
+            }
+            self.order
+                .iter()
+                .map(|&q| (q, self.mapping[q]))
+                .filter(|&(_, hq)| hq != usize::MAX)
+                .all(|(q, hq)| {
+                    let forward = !pm[p][q] || hm[h][hq];
+                    let backward = !pm[q][p] || hm[hq][h];
+                    let reflected = !self.exact || (pm[p][q] == hm[h][hq] && pm[q][p] == hm[hq][h]);
+                    forward && backward && reflected
+                })
+        }
+
+        fn search(&mut self, depth: usize) {
+            if depth == self.order.len() {
+                self.found.push(self.mapping.clone());
+                return;
+            }
+            let p = self.order[depth];
+            for h in 0..self.host.nodes.len() {
+                if self.first_only && !self.found.is_empty() {
+                    return;
+                }
+                if self.used[h] || !self.feasible(p, h) {
+                    continue;
+                }
+                self.mapping[p] = h;
+                self.used[h] = true;
+                self.search(depth + 1);
+                self.used[h] = false;
+                self.mapping[p] = usize::MAX;
+            }
+        }
+    }
+
+    // Graph queries works both on directed graph and undirected graph
+    impl Graph {
+        pub fn to_matrix(&self) -> Vec<Vec<bool>> {
+            self.to_matrix_as(self.directed)
+        }
+
+        // adjacency matrix read as directed or undirected regardless of the
+        // `directed` flag, so queries never have to flip it
+        fn to_matrix_as(&self, directed: bool) -> Vec<Vec<bool>> {
+            self.links.iter().fold(
+                vec![vec![false; self.nodes.len()]; self.nodes.len()],
+                |mut rows, (_, l)| {
+                    rows[self.nodes_map[&l.source]][self.nodes_map[&l.target]] = true;
+                    if !directed {
+                        rows[self.nodes_map[&l.target]][self.nodes_map[&l.source]] = true;
+                    }
+                    rows
+                },
+            )
+        }
+
+        pub fn direct_connected(&self, source_id: &str) -> Vec<Node> {
+            self.direct_connected_as(source_id, self.directed)
+        }
+
+        // ids of the same neighbors as `direct_connected`, borrowed from the
+        // links and produced lazily, in no particular order
+        pub fn neighbor_ids<'a>(&'a self, node_id: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+            self.links.values().filter_map(move |l| {
+                if l.source == node_id {
+                    Some(l.target.as_str())
+                } else if !self.directed && l.target == node_id {
+                    Some(l.source.as_str())
+                } else {
+                    None
+                }
+            })
+        }
+
+        // successors and predecessors together, whatever the `directed` flag
+        // says, in node order and each once
+        pub fn all_neighbors(&self, node_id: &str) -> Vec<Node> {
+            self.direct_connected_as(node_id, false)
+        }
+
+        fn direct_connected_as(&self, source_id: &str, directed: bool) -> Vec<Node> {
+            if !self.nodes_map.contains_key(source_id) {
+                return Vec::new();
+            }
+            let m = self.to_bitset_matrix_as(directed);
+            let row = &m[self.nodes_map[source_id]];
+            (0..self.nodes.len())
+                .into_par_iter()
+                .filter_map(|idx| {
+                    if bitset_contains(row, idx) {
+                        Some(self.nodes[idx].clone())
+                    } else {
+                        None
+                    }
+                })
+                .collect::<Vec<Node>>()
+        }
+
+        // same as `to_matrix` but every row is packed one bit per node, bit j
+        // of row i lives at `row[j / 64] >> (j % 64)`
+        pub fn to_bitset_matrix(&self) -> Vec<Vec<u64>> {
+            self.to_bitset_matrix_as(self.directed)
+        }
+
+        fn to_bitset_matrix_as(&self, directed: bool) -> Vec<Vec<u64>> {
+            let words = self.nodes.len().div_ceil(64);
+            self.links
+                .values()
+                .fold(vec![vec![0u64; words]; self.nodes.len()], |mut rows, l| {
+                    let (s, t) = (self.nodes_map[&l.source], self.nodes_map[&l.target]);
+                    rows[s][t / 64] |= 1 << (t % 64);
+                    if !directed {
+                        rows[t][s / 64] |= 1 << (s % 64);
+                    }
+                    rows
+                })
+        }
+
+        pub fn get_node(&self, id: &str) -> Option<Node> {
+            if self.nodes_map.contains_key(id) {
+                Some(self.nodes[self.nodes_map[id]].clone())
+            } else {
+                None
+            }
+        }
+
+        // position of the node in `nodes`, which is also its row and column
+        // in `to_matrix`
+        pub fn node_index(&self, id: &str) -> Option<usize> {
+            self.nodes_map.get(id).cloned()
+        }
+
+        pub fn get_link(&self, source: &str, target: &str) -> Option<Link> {
+            let key = &self.link_key(source, target);
+            if self.links.contains_key(key) {
+                Some(self.links[key].clone())
+            } else {
+                None
+            }
+        }
+
+        // number of links going from a node of set_a to a node of set_b, on
+        // undirected graphs a link counts when it joins the two sets either
+        // way. every link is counted at most once, so with overlapping sets a
+        // link inside the shared part counts once as well
+        pub fn edges_between_sets(&self, set_a: &[String], set_b: &[String]) -> usize {
+            let a: HashSet<&str> = set_a.iter().map(|id| id.as_str()).collect();
+            let b: HashSet<&str> = set_b.iter().map(|id| id.as_str()).collect();
+            self.links
+                .values()
+                .filter(|l| {
+                    let (s, t) = (l.source.as_str(), l.target.as_str());
+                    (a.contains(s) && b.contains(t))
+                        || (!self.directed && b.contains(s) && a.contains(t))
+                })
+                .count()
+        }
+
+        // whether a links to b, on undirected graphs b to a counts as well.
+        // both key orders are checked, so links stored before the `directed`
+        // flag was switched off are still found
+        pub fn edge_exists(&self, a: &str, b: &str) -> bool {
+            self.links.contains_key(&make_link_key(a, b))
+                || !self.directed && self.links.contains_key(&make_link_key(b, a))
+        }
+
+        // weighted degree: sum of the weights of every link touching the node,
+        // each link counts 1.0 when the graph is unweighted
+        pub fn strength(&self, node_id: &str) -> f64 {
+            self.links.values().fold(0.0, |sum, l| {
+                let w = if self.weighted { l.weight } else { 1.0 };
+                let touches = (l.source == node_id) as u8 + (l.target == node_id) as u8;
+                sum + w * touches as f64
+            })
+        }
+
+        // sums link weights (or hops when unweighted) along the path, None
+        // when the path is empty, has a missing node or an unlinked pair
+        pub fn path_cost(&self, path: &[Node]) -> Option<f64> {
+            if path.is_empty() || !path.iter().all(|n| self.nodes_map.contains_key(&n.id)) {
+                return None;
+            }
+            path.windows(2).try_fold(0.0, |cost, pair| {
+                let key = self.link_key(&pair[0].id, &pair[1].id);
+                let l = self.links.get(&key)?;
+                Some(cost + if self.weighted { l.weight } else { 1.0 })
+            })
+        }
+
+        pub fn is_valid_path(&self, path: &[Node]) -> bool {
+            self.path_cost(path).is_some()
+        }
+
+        // links sorted ascending by weight, ties are broken by link key so the
+        // order is deterministic
+        pub fn links_by_weight(&self) -> Vec<&Link> {
+            let mut entries: Vec<(&String, &Link)> = self.links.iter().collect();
+            entries.sort_by(|(ka, a), (kb, b)| a.weight.total_cmp(&b.weight).then(ka.cmp(kb)));
+            entries.into_iter().map(|(_, l)| l).collect()
+        }
+
+        // (node index, link weight) of every out neighbor sorted by index,
+        // undirected links count both ways
+        fn neighbor_indices(&self, node_id: &str) -> Vec<(usize, f64)> {
+            let mut neighbors: Vec<(usize, f64)> = self
+                .links
+                .values()
+                .filter_map(|l| {
+                    if l.source == node_id {
+                        Some((self.nodes_map[&l.target], l.weight))
+                    } else if !self.directed && l.target == node_id {
+                        Some((self.nodes_map[&l.source], l.weight))
+                    } else {
+                        None
+                    }
+                })
+                .collect();
+            neighbors.sort_by_key(|&(idx, _)| idx);
+            neighbors
+        }
+
+        // every out neighbor with the weight of the link leading to it, in
+        // node order. undirected links count both ways
+        pub fn weighted_neighbors(&self, node_id: &str) -> Vec<(Node, f64)> {
+            self.neighbor_indices(node_id)
+                .into_iter()
+                .map(|(idx, w)| (self.nodes[idx].clone(), w))
+                .collect()
+        }
+
+        // picks one out neighbor at random with probability proportional to
+        // the link weight, or uniformly when the graph is unweighted or all
+        // weights are zero. undirected links count both ways, None when the
+        // node has no out neighbor. the same seed gives the same pick
+        pub fn sample_neighbor(&self, node_id: &str, seed: u64) -> Option<Node> {
+            let candidates = self.neighbor_indices(node_id);
+            if candidates.is_empty() {
+                return None;
+            }
+            let total: f64 = candidates.iter().map(|&(_, w)| w).sum();
+            let mut rng = StdRng::seed_from_u64(seed);
+            if !self.weighted || total <= 0.0 {
+                let (idx, _) = candidates[rng.gen_range(0..candidates.len())];
+                return Some(self.nodes[idx].clone());
+            }
+            let mut r = rng.gen::<f64>() * total;
+            let picked = candidates
+                .iter()
+                .find(|&&(_, w)| {
+                    r -= w;
+                    r < 0.0
+                })
+                .unwrap_or(&candidates[candidates.len() - 1]);
+            Some(self.nodes[picked.0].clone())
+        }
+
+        // name => ids of the nodes carrying it, for names used by two or more
+        // nodes. ids keep the node order, empty names are not reported since
+        // nodes created implicitly by add_link have no name
+        pub fn find_duplicate_names(&self) -> HashMap<String, Vec<String>> {
+            let mut by_name: HashMap<String, Vec<String>> = HashMap::new();
+            self.nodes
+                .iter()
+                .filter(|n| !n.name.is_empty())
+                .for_each(|n| {
+                    by_name
+                        .entry(n.name.clone())
+                        .or_default()
+                        .push(n.id.clone())
+                });
+            by_name.retain(|_, ids| ids.len() > 1);
+            by_name
+        }
+
+        // every link having node_id as source or target, unlike
+        // `direct_connected` this gives the links themselves and includes in
+        // links on directed graphs. each link appears once, ordered by key
+        pub fn incident_links(&self, node_id: &str) -> Vec<&Link> {
+            let mut entries: Vec<(&String, &Link)> = self
+                .links
+                .iter()
+                .filter(|(_, l)| l.source == node_id || l.target == node_id)
+                .collect();
+            entries.sort_by_key(|(k, _)| *k);
+            entries.into_iter().map(|(_, l)| l).collect()
+        }
+
+        // checks that `nodes_map`, `links` keys and the degree counters agree
+        // with the nodes and links, e.g. after editing the public fields by
+        // hand or deserializing. returns every problem found
+        pub fn validate(&self) -> Result<(), Vec<String>> {
+            let mut problems = Vec::new();
+            let mut mapped: Vec<(&String, &usize)> = self.nodes_map.iter().collect();
+            mapped.sort();
+            mapped
+                .iter()
+                .for_each(|(id, &idx)| match self.nodes.get(idx) {
+                    Some(n) if n.id == **id => {}
+                    Some(n) => problems.push(format!(
+                        "[ERROR] node {} maps to index {} which holds node {}",
+                        id, idx, n.id
+                    )),
+                    None => problems.push(format!(
+                        "[ERROR] node {} maps to index {} out of bounds",
+                        id, idx
+                    )),
+                });
+            self.nodes
+                .iter()
+                .filter(|n| !self.nodes_map.contains_key(&n.id))
+                .for_each(|n| {
+                    problems.push(format!("[ERROR] node {} is missing from nodes_map", n.id))
+                });
+            let mut keys: Vec<&String> = self.links.keys().collect();
+            keys.sort();
+            keys.iter().for_each(|k| {
+                let l = &self.links[*k];
+                [&l.source, &l.target]
+                    .iter()
+                    .filter(|id| !self.nodes_map.contains_key(**id))
+                    .for_each(|id| {
+                        problems.push(format!("[ERROR] link {} refers to unknown node {}", k, id))
+                    });
+                if **k != self.link_key(&l.source, &l.target) {
+                    problems.push(format!(
+                        "[ERROR] link {} to {} is stored under key {}",
+                        l.source, l.target, k
+                    ));
+                }
+            });
+            if problems.is_empty() {
+                let mut check = self.clone();
+                check.rebuild_indexes();
+                if check.in_degrees != self.in_degrees || check.out_degrees != self.out_degrees {
+                    problems.push("[ERROR] degree counters are out of date".to_string());
+                }
+            }
+            if problems.is_empty() {
+                Ok(())
+            } else {
+                Err(problems)
+            }
+        }
+
+        // (source, target, weight) of every link, ordered by the node positions
+        // of source and then target so the output is stable between runs
+        pub fn edge_tuples(&self) -> impl Iterator<Item = (String, String, f64)> {
+            let mut tuples: Vec<(usize, usize, &Link)> = self
+                .links
+                .values()
+                .map(|l| (self.nodes_map[&l.source], self.nodes_map[&l.target], l))
+                .collect();
+            tuples.sort_by_key(|&(s, t, _)| (s, t));
+            tuples
+                .into_iter()
+                .map(|(_, _, l)| (l.source.clone(), l.target.clone(), l.weight))
+                .collect::<Vec<_>>()
+                .into_iter()
+        }
+
+        // fraction of links a -> b for which b -> a also exists, 0.0 without
+        // links. every link of an undirected graph is reciprocal
+        pub fn reciprocity(&self) -> f64 {
+            if self.links.is_empty() {
+                return 0.0;
+            }
+            if !self.directed {
+                return 1.0;
+            }
+            let reciprocal = self
+                .links
+                .values()
+                .filter(|l| {
+                    self.links
+                        .contains_key(&make_link_key(&l.target, &l.source))
+                })
+                .count();
+            reciprocal as f64 / self.links.len() as f64
+        }
+
+        // links whose source is also their target, sorted by key
+        pub fn self_loops(&self) -> Vec<&Link> {
+            let mut loops: Vec<(&String, &Link)> = self
+                .links
+                .iter()
+                .filter(|(_, l)| l.source == l.target)
+                .collect();
+            loops.sort_by(|a, b| a.0.cmp(b.0));
+            loops.into_iter().map(|(_, l)| l).collect()
+        }
+
+        pub fn has_self_loops(&self) -> bool {
+            self.links.values().any(|l| l.source == l.target)
+        }
+
+        // size of `neighbor_ids` from the degree counters: the out degree on
+        // directed graphs, on undirected ones a self loop counts once
+        fn neighbor_count(&self, node_id: &str) -> usize {
+            if self.directed {
+                return self.outdegree(node_id);
+            }
+            let self_loop = self.links.contains_key(&self.link_key(node_id, node_id));
+            self.degree_centrality(node_id) - self_loop as usize
+        }
+
+        // adamic-adar link prediction score: sum of 1 / ln(degree) over the
+        // common neighbors of a and b. neighbors follow `neighbor_ids`,
+        // common neighbors with degree 1 contribute nothing instead of 1/0
+        pub fn adamic_adar(&self, a: &str, b: &str) -> f64 {
+            let b_neighbors: HashSet<&str> = self.neighbor_ids(b).collect();
+            self.neighbor_ids(a)
+                .filter(|id| b_neighbors.contains(id))
+                .map(|id| self.neighbor_count(id) as f64)
+                .filter(|&degree| degree > 1.0)
+                .map(|degree| 1.0 / degree.ln())
+                .sum()
+        }
+
+        // preferential attachment link prediction score: product of the
+        // neighbor counts of a and b
+        pub fn preferential_attachment(&self, a: &str, b: &str) -> f64 {
+            (self.neighbor_count(a) * self.neighbor_count(b)) as f64
+        }
+
+        // neighbor id => link weight, 1.0 per link when unweighted
+        fn neighbor_vector(&self, node_id: &str) -> HashMap<String, f64> {
+            self.neighbor_indices(node_id)
+                .into_iter()
+                .map(|(idx, w)| {
+                    (
+                        self.nodes[idx].id.clone(),
+                        if self.weighted { w } else { 1.0 },
+                    )
+                })
+                .collect()
+        }
+
+        // shared neighbors over all neighbors of a and b, neighbors follow
+        // `weighted_neighbors`. 0.0 when both have no neighbors
+        pub fn jaccard_similarity(&self, a: &str, b: &str) -> f64 {
+            let a: HashSet<String> = self.neighbor_vector(a).into_keys().collect();
+            let b: HashSet<String> = self.neighbor_vector(b).into_keys().collect();
+            let union = a.union(&b).count();
+            if union == 0 {
+                return 0.0;
+            }
+            a.intersection(&b).count() as f64 / union as f64
+        }
+
+        // jaccard over the weighted neighbor vectors: sum of the smaller
+        // weight over sum of the larger weight per neighbor. equals
+        // jaccard_similarity on unweighted graphs, 0.0 for empty neighborhoods
+        pub fn weighted_jaccard(&self, a: &str, b: &str) -> f64 {
+            let (va, vb) = (self.neighbor_vector(a), self.neighbor_vector(b));
+            let ids: HashSet<&String> = va.keys().chain(vb.keys()).collect();
+            let (min, max) = ids.iter().fold((0.0, 0.0), |(min, max), id| {
+                let (x, y) = (
+                    va.get(*id).copied().unwrap_or(0.0),
+                    vb.get(*id).copied().unwrap_or(0.0),
+                );
+                (min + x.min(y), max + x.max(y))
+            });
+            if max > 0.0 {
+                min / max
+            } else {
+                0.0
+            }
+        }
+
+        // cosine of the angle between the weighted neighbor vectors of a and
+        // b, 0.0 when either neighborhood is empty
+        pub fn cosine_similarity(&self, a: &str, b: &str) -> f64 {
+            let (va, vb) = (self.neighbor_vector(a), self.neighbor_vector(b));
+            let dot: f64 = va
+                .iter()
+                .filter_map(|(id, x)| vb.get(id).map(|y| x * y))
+                .sum();
+            let norm = |v: &HashMap<String, f64>| v.values().map(|x| x * x).sum::<f64>().sqrt();
+            let (na, nb) = (norm(&va), norm(&vb));
+            if na > 0.0 && nb > 0.0 {
+                dot / (na * nb)
+            } else {
+                0.0
+            }
+        }
+
+        // finds all connected components in a graph, links are followed both
+        // ways so directed graphs get their weakly connected components.
+        // one breadth first pass over an adjacency list, the components are
+        // ordered by the index of their first node
+        pub fn connected_components(&self) -> Vec<Vec<Node>> {
+            let mut adj = vec![Vec::new(); self.nodes.len()];
+            self.links.values().for_each(|l| {
+                let (s, t) = (self.nodes_map[&l.source], self.nodes_map[&l.target]);
+                adj[s].push(t);
+                adj[t].push(s);
+            });
+            let mut seen = vec![false; self.nodes.len()];
+            let mut components = Vec::new();
+            for root in 0..self.nodes.len() {
+                if seen[root] {
+                    continue;
+                }
+                seen[root] = true;
+                let mut component = vec![root];
+                let mut i = 0;
+                while i < component.len() {
+                    adj[component[i]].iter().for_each(|&v| {
+                        if !seen[v] {
+                            seen[v] = true;
+                            component.push(v);
+                        }
+                    });
+                    i += 1;
+                }
+                components.push(
+                    component
+                        .into_iter()
+                        .map(|i| self.nodes[i].clone())
+                        .collect(),
+                );
+            }
+            components
+        }
+
+        // density of the links among nodes whose degree is greater than k,
+        // 0.0 when fewer than two nodes pass the threshold
+        pub fn rich_club_coefficient(&self, k: usize) -> f64 {
+            let ids: Vec<String> = self.nodes.iter().map(|n| n.id.clone()).collect();
+            let rich: Vec<String> = ids
+                .into_iter()
+                .filter(|id| self.degree_centrality(id) > k)
+                .collect();
+            let n = rich.len() as f64;
+            if rich.len() < 2 {
+                return 0.0;
+            }
+            let links = self
+                .induced_subgraph(&rich)
+                .links
+                .values()
+                .filter(|l| l.source != l.target)
+                .count() as f64;
+            let possible = if self.directed {
+                n * (n - 1.0)
+            } else {
+                n * (n - 1.0) / 2.0
+            };
+            links / possible
+        }
+
+        // node count of the biggest connected component, components are
+        // weakly connected for directed graphs
+        pub fn largest_component_size(&self) -> usize {
+            self.connected_components()
+                .iter()
+                .map(|c| c.len())
+                .max()
+                .unwrap_or(0)
+        }
+
+        // burt's network constraint over the undirected neighborhood:
+        //   c(i) = sum over neighbors j of (p(i,j) + sum over q != i,j of p(i,q) * p(q,j))^2
+        // where p(i,j) is the share of i's tie strength going to j and the tie
+        // strength is the summed weight of the links both ways (1.0 per link
+        // when unweighted). high values mean the contacts are tied to each
+        // other. a single contact gives 1.0, isolated nodes get 0.0
+        pub fn burt_constraint(&self, node_id: &str) -> f64 {
+            let mut ties: HashMap<&str, HashMap<&str, f64>> = HashMap::new();
+            self.links
+                .values()
+                .filter(|l| l.source != l.target)
+                .for_each(|l| {
+                    let w = if self.weighted { l.weight } else { 1.0 };
+                    *ties
+                        .entry(&l.source)
+                        .or_default()
+                        .entry(&l.target)
+                        .or_default() += w;
+                    *ties
+                        .entry(&l.target)
+                        .or_default()
+                        .entry(&l.source)
+                        .or_default() += w;
+                });
+            let share = |i: &str, j: &str| -> f64 {
+                ties.get(i).map_or(0.0, |t| {
+                    let total: f64 = t.values().sum();
+                    if total > 0.0 {
+                        t.get(j).copied().unwrap_or(0.0) / total
+                    } else {
+                        0.0
+                    }
+                })
+            };
+            let contacts = match ties.get(node_id) {
+                Some(t) => t,
+                None => return 0.0,
+            };
+            contacts
+                .keys()
+                .map(|&j| {
+                    let indirect: f64 = contacts
+                        .keys()
+                        .filter(|&&q| q != j)
+                        .map(|&q| share(node_id, q) * share(q, j))
+                        .sum();
+                    (share(node_id, j) + indirect).powi(2)
+                })
+                .sum()
+        }
+
+        // coreness of every node: the largest k for which the node is in the
+        // k-core, links taken both ways and self loops ignored. nodes are
+        // peeled in order of current degree using degree buckets
+        // (batagelj-zaversnik), so this is O(V + E)
+        pub fn core_numbers(&self) -> HashMap<String, usize> {
+            let n = self.nodes.len();
+            let mut adj: Vec<Vec<usize>> = vec![Vec::new(); n];
+            self.links.values().for_each(|l| {
+                let (s, t) = (self.nodes_map[&l.source], self.nodes_map[&l.target]);
+                if s != t {
+                    adj[s].push(t);
+                    adj[t].push(s);
+                }
+            });
+            adj.iter_mut().for_each(|neighbors| {
+                neighbors.sort_unstable();
+                neighbors.dedup();
+            });
+            let mut degree: Vec<usize> = adj.iter().map(|neighbors| neighbors.len()).collect();
+            let max_degree = degree.iter().copied().max().unwrap_or(0);
+            // nodes sorted by degree, bin_start[d] is where degree d begins
+            let mut bin_start = vec![0; max_degree + 1];
+            degree.iter().for_each(|&d| bin_start[d] += 1);
+            let mut start = 0;
+            bin_start.iter_mut().for_each(|count| {
+                let c = *count;
+                *count = start;
+                start += c;
+            });
+            let mut order = vec![0; n];
+            let mut position = vec![0; n];
+            let mut next = bin_start.clone();
+            (0..n).for_each(|v| {
+                position[v] = next[degree[v]];
+                order[position[v]] = v;
+                next[degree[v]] += 1;
+            });
+            for i in 0..n {
+                let v = order[i];
+                for &u in &adj[v] {
+                    if degree[u] > degree[v] {
+                        // swap u with the first node of its bin, then shrink it
+                        let du = degree[u];
+                        let (pu, pw) = (position[u], bin_start[du]);
+                        let w = order[pw];
+                        if u != w {
+                            order.swap(pu, pw);
+                            position[u] = pw;
+                            position[w] = pu;
+                        }
+                        bin_start[du] += 1;
+                        degree[u] -= 1;
+                    }
+                }
+            }
+            self.nodes
+                .iter()
+                .zip(degree)
+                .map(|(node, core)| (node.id.clone(), core))
+                .collect()
+        }
+
+        // same node ids and links (and link weights when either graph is
+        // weighted) on the same kind of graph. unlike `==` node names, link
+        // labels, attributes and node order are not compared
+        pub fn structurally_equal(&self, other: &Graph) -> bool {
+            if self.directed != other.directed
+                || self.nodes.len() != other.nodes.len()
+                || self.links.len() != other.links.len()
+                || self
+                    .nodes
+                    .iter()
+                    .any(|n| !other.nodes_map.contains_key(&n.id))
+            {
+                return false;
+            }
+            let weighted = self.weighted || other.weighted;
+            self.links.iter().all(|(k, l)| match other.links.get(k) {
+                Some(o) => {
+                    let same_ends = (&l.source, &l.target) == (&o.source, &o.target)
+                        || (!self.directed && (&l.source, &l.target) == (&o.target, &o.source));
+                    same_ends && (!weighted || l.weight == o.weight)
+                }
+                None => false,
+            })
+        }
+
+        // whether the two graphs have the same structure up to renaming the
+        // node ids, names, labels and weights are ignored. the search is
+        // exponential in the worst case, so it is meant for small graphs of
+        // up to a few dozen nodes
+        pub fn is_isomorphic(&self, other: &Graph) -> bool {
+            if self.directed != other.directed
+                || self.nodes.len() != other.nodes.len()
+                || self.links.len() != other.links.len()
+            {
+                return false;
+            }
+            let mut matcher = StructureMatcher::new(self, other, true, false);
+            let mut ours = matcher.pattern_degrees.clone();
+            let mut theirs = matcher.host_degrees.clone();
+            ours.sort_unstable();
+            theirs.sort_unstable();
+            if ours != theirs {
+                return false;
+            }
+            matcher.first_only = true;
+            matcher.search(0);
+            !matcher.found.is_empty()
+        }
+
+        // every way the pattern occurs in this graph, as pattern node id =>
+        // node id maps. pattern links must exist between the mapped nodes but
+        // extra links among them are allowed, and pattern nodes with a non
+        // empty name only match nodes of the same name. symmetric patterns
+        // show up once per automorphism, e.g. each triangle matches 6 times
+        pub fn find_subgraph_matches(&self, pattern: &Graph) -> Vec<HashMap<String, String>> {
+            if pattern.nodes.len() > self.nodes.len() {
+                return Vec::new();
+            }
+            let mut matcher = StructureMatcher::new(pattern, self, false, true);
+            matcher.search(0);
+            matcher
+                .found
+                .iter()
+                .map(|mapping| {
+                    mapping
+                        .iter()
+                        .enumerate()
+                        .map(|(p, &h)| (pattern.nodes[p].id.clone(), self.nodes[h].id.clone()))
+                        .collect()
+                })
+                .collect()
+        }
+    }
+
+    // Graph queries only works on directed graph
+    impl Graph {
+        // served from the degree counters, the matrix is only built when
+        // they are missing, e.g. after `nodes` was pushed to directly
+        pub fn indegree(&self, node_id: &str) -> usize {
+            match self.nodes_map.get(node_id) {
+                Some(&idx) if self.in_degrees.len() == self.nodes.len() => self.in_degrees[idx],
+                Some(&idx) => self.indegree_from_matrix(idx),
+                None => 0,
+            }
+        }
+
+        pub fn outdegree(&self, node_id: &str) -> usize {
+            match self.nodes_map.get(node_id) {
+                Some(&idx) if self.out_degrees.len() == self.nodes.len() => self.out_degrees[idx],
+                Some(&idx) => self.outdegree_from_matrix(idx),
+                None => 0,
+            }
+        }
+
+        fn indegree_from_matrix(&self, node_idx: usize) -> usize {
+            let m = self.to_matrix_as(true);
+            m.iter().fold(0, |mut indegree, row| {
+                if row[node_idx] {
+                    indegree += 1;
+                }
+                indegree
+            })
+        }
+
+        fn outdegree_from_matrix(&self, node_idx: usize) -> usize {
+            let m = self.to_matrix_as(true);
+            m[node_idx]
+                .iter()
+                .filter(|&&is_connected| is_connected)
+                .count()
+        }
+
+        pub fn degree_centrality(&self, node_id: &str) -> usize {
+            self.indegree(node_id) + self.outdegree(node_id)
+        }
+
+        // in degree + out degree of every node sorted from high to low, read
+        // from the degree counters. on undirected graphs this is the plain
+        // degree
+        pub fn degree_sequence(&self) -> Vec<usize> {
+            let mut degrees: Vec<usize> = self
+                .nodes
+                .iter()
+                .map(|n| self.degree_centrality(&n.id))
+                .collect();
+            degrees.sort_unstable_by(|a, b| b.cmp(a));
+            degrees
+        }
+
+        // (nodes without in links, nodes without out links) in node order,
+        // read from the degree counters or counted in one pass over the links
+        // when the counters are missing. isolated nodes are in both
+        pub fn sources_and_sinks(&self) -> (Vec<Node>, Vec<Node>) {
+            let (ins, outs) = if self.in_degrees.len() == self.nodes.len() {
+                (self.in_degrees.clone(), self.out_degrees.clone())
+            } else {
+                let mut ins = vec![0; self.nodes.len()];
+                let mut outs = vec![0; self.nodes.len()];
+                self.links.values().for_each(|l| {
+                    outs[self.nodes_map[&l.source]] += 1;
+                    ins[self.nodes_map[&l.target]] += 1;
+                });
+                (ins, outs)
+            };
+            let pick = |degrees: &[usize]| {
+                self.nodes
+                    .iter()
+                    .zip(degrees)
+                    .filter(|(_, &d)| d == 0)
+                    .map(|(n, _)| n.clone())
+                    .collect()
+            };
+            (pick(&ins), pick(&outs))
+        }
+
+        // (min, average, max) of in degree + out degree over all nodes, read
+        // from the degree counters. (0, 0.0, 0) for an empty graph
+        pub fn degree_stats(&self) -> (usize, f64, usize) {
+            if self.nodes.is_empty() {
+                return (0, 0.0, 0);
+            }
+            let (min, sum, max) =
+                self.nodes
+                    .iter()
+                    .fold((usize::MAX, 0, 0), |(min, sum, max), n| {
+                        let d = self.degree_centrality(&n.id);
+                        (min.min(d), sum + d, max.max(d))
+                    });
+            (min, sum as f64 / self.nodes.len() as f64, max)
+        }
+
+        pub fn pagerank_centrality(&self, _node_id: &str) -> f64 {
+            0.0
+        }
+
+        // pagerank where each node passes its rank along its out links in
+        // proportion to their weights instead of evenly, undirected links
+        // count both ways. rank of nodes without out links (or with zero total
+        // weight) is spread over all nodes. power iteration stops when the
+        // ranks move less than 1e-10 in total or after 100 rounds
+        pub fn weighted_pagerank(&self, damping: f64) -> HashMap<String, f64> {
+            let n = self.nodes.len();
+            if n == 0 {
+                return HashMap::new();
+            }
+            let mut out: Vec<Vec<(usize, f64)>> = vec![Vec::new(); n];
+            self.links.values().for_each(|l| {
+                let (s, t) = (self.nodes_map[&l.source], self.nodes_map[&l.target]);
+                out[s].push((t, l.weight));
+                if !self.directed && s != t {
+                    out[t].push((s, l.weight));
+                }
+            });
+            let totals: Vec<f64> = out
+                .iter()
+                .map(|edges| edges.iter().map(|&(_, w)| w).sum())
+                .collect();
+            let mut rank = vec![1.0 / n as f64; n];
+            for _ in 0..100 {
+                let dangling: f64 = (0..n).filter(|&u| totals[u] <= 0.0).map(|u| rank[u]).sum();
+                let base = (1.0 - damping + damping * dangling) / n as f64;
+                let mut next = vec![base; n];
+                (0..n).filter(|&u| totals[u] > 0.0).for_each(|u| {
+                    out[u]
+                        .iter()
+                        .for_each(|&(v, w)| next[v] += damping * rank[u] * w / totals[u]);
+                });
+                let delta: f64 = next.iter().zip(&rank).map(|(a, b)| (a - b).abs()).sum();
+                rank = next;
+                if delta < 1e-10 {
+                    break;
+                }
+            }
+            self.nodes
+                .iter()
+                .zip(rank)
+                .map(|(node, r)| (node.id.clone(), r))
+                .collect()
+        }
+    }
+
+    // Graph transformations, these build a new graph and leave self untouched
+    impl Graph {
+        // the given nodes and every link between them, flags are preserved
+        // and ids that are not in the graph are ignored. links that collide
+        // under the link key, e.g. a-b and b-a after `directed` was switched
+        // off, are kept once
+        pub fn induced_subgraph(&self, ids: &[String]) -> Graph {
+            let keep: HashSet<&str> = ids.iter().map(|id| id.as_str()).collect();
+            let mut g = Graph::new();
+            g.directed = self.directed;
+            g.weighted = self.weighted;
+            self.nodes
+                .iter()
+                .filter(|n| keep.contains(n.id.as_str()))
+                .for_each(|n| {
+                    g.add_node(n).unwrap();
+                });
+            self.links
+                .values()
+                .filter(|l| keep.contains(l.source.as_str()) && keep.contains(l.target.as_str()))
+                .for_each(|l| {
+                    let _ = g.add_link(l);
+                });
+            g
+        }
+
+        // every connected component as a standalone graph with its own nodes
+        // and links, in the order of `connected_components`. links are
+        // followed both ways, so weakly connected parts of a directed graph
+        // end up together. flags are preserved
+        pub fn component_subgraphs(&self) -> Vec<Graph> {
+            self.connected_components()
+                .iter()
+                .map(|component| {
+                    let ids: Vec<String> = component.iter().map(|n| n.id.clone()).collect();
+                    self.induced_subgraph(&ids)
+                })
+                .collect()
+        }
+
+        // nodes and links of self that are not in other, matched by node id
+        // and link key. a node that other also has is still kept when one of
+        // the remaining links needs it as an endpoint, so the result is always
+        // a valid graph. flags are taken from self and links that collide
+        // under the link key are kept once
+        pub fn difference(&self, other: &Graph) -> Graph {
+            let links: Vec<&Link> = self
+                .links
+                .iter()
+                .filter(|(k, _)| !other.links.contains_key(*k))
+                .map(|(_, l)| l)
+                .collect();
+            let endpoints: HashSet<&str> = links
+                .iter()
+                .flat_map(|l| vec![l.source.as_str(), l.target.as_str()])
+                .collect();
+            let mut g = Graph::new();
+            g.directed = self.directed;
+            g.weighted = self.weighted;
+            self.nodes
+                .iter()
+                .filter(|n| {
+                    !other.nodes_map.contains_key(&n.id) || endpoints.contains(n.id.as_str())
+                })
+                .for_each(|n| {
+                    g.add_node(n).unwrap();
+                });
+            links.iter().for_each(|l| {
+                let _ = g.add_link(l);
+            });
+            g
+        }
+
+        // nodes and links present in both graphs, matched by node id and link
+        // key. node data, link weights, labels and attributes as well as the
+        // flags come from self when the two disagree
+        pub fn intersection(&self, other: &Graph) -> Graph {
+            let mut g = Graph::new();
+            g.directed = self.directed;
+            g.weighted = self.weighted;
+            self.nodes
+                .iter()
+                .filter(|n| other.nodes_map.contains_key(&n.id))
+                .for_each(|n| {
+                    g.add_node(n).unwrap();
+                });
+            let mut keys: Vec<&String> = self
+                .links
+                .keys()
+                .filter(|k| other.links.contains_key(*k))
+                .collect();
+            keys.sort();
+            keys.iter().for_each(|k| {
+                let _ = g.add_link(&self.links[*k]);
+            });
+            g
+        }
+
+        // the line graph: one node per link, named after the link key and
+        // labelled with the link label. on undirected graphs two links are
+        // joined when they share an endpoint, on directed graphs a -> b is
+        // linked to every b -> c so that paths are kept
+        pub fn line_graph(&self) -> Graph {
+            let mut g = Graph::new();
+            g.directed = self.directed;
+            let mut keys: Vec<&String> = self.links.keys().collect();
+            keys.sort();
+            keys.iter().for_each(|k| {
+                g.add_node(&Node::new(k.to_string(), self.links[*k].label.clone()))
+                    .unwrap();
+            });
+            for (i, ka) in keys.iter().enumerate() {
+                let a = &self.links[*ka];
+                let candidates = if self.directed {
+                    &keys[..]
+                } else {
+                    &keys[i + 1..]
+                };
+                candidates.iter().filter(|kb| *kb != ka).for_each(|kb| {
+                    let b = &self.links[*kb];
+                    let joined = if self.directed {
+                        a.target == b.source
+                    } else {
+                        a.source == b.source
+                            || a.source == b.target
+                            || a.target == b.source
+                            || a.target == b.target
+                    };
+                    if joined {
+                        let _ = g.add_link(&Link::new(
+                            ka.to_string(),
+                            kb.to_string(),
+                            "".to_string(),
+                            1.0,
+                        ));
+                    }
+                });
+            }
+            g
+        }
+
+        // keeps every node independently with probability node_fraction and
+        // returns the induced subgraph, panics when the fraction is not in [0, 1]
+        pub fn random_subgraph(&self, node_fraction: f64, seed: u64) -> Graph {
+            assert!(
+                (0.0..=1.0).contains(&node_fraction),
+                "node_fraction must be in [0, 1], got {}",
+                node_fraction
+            );
+            let mut rng = StdRng::seed_from_u64(seed);
+            let kept: Vec<String> = self
+                .nodes
+                .iter()
+                .filter(|_| rng.gen::<f64>() < node_fraction)
+                .map(|n| n.id.clone())
+                .collect();
+            self.induced_subgraph(&kept)
+        }
+
+        // same nodes, linked exactly where self has no link. self loops are
+        // never added and new links have weight 1.0 and an empty label
+        pub fn complement(&self) -> Graph {
+            let mut g = Graph::new();
+            g.directed = self.directed;
+            g.weighted = self.weighted;
+            self.nodes.iter().for_each(|n| {
+                g.add_node(n).unwrap();
+            });
+            let m = self.to_matrix();
+            for (i, s) in self.nodes.iter().enumerate() {
+                for (j, t) in self.nodes.iter().enumerate() {
+                    if i == j || m[i][j] || (!self.directed && j < i) {
+                        continue;
+                    }
+                    g.add_link(&Link::new(s.id.clone(), t.id.clone(), "".to_string(), 1.0))
+                        .unwrap();
+                }
+            }
+            g
+        }
+
+        // every node is kept, only links with weight >= min_weight survive.
+        // links that collide under the link key are kept once
+        pub fn filter_edges_by_weight(&self, min_weight: f64) -> Graph {
+            let mut g = Graph::new();
+            g.directed = self.directed;
+            g.weighted = self.weighted;
+            self.nodes.iter().for_each(|n| {
+                g.add_node(n).unwrap();
+            });
+            self.links
+                .values()
+                .filter(|l| l.weight >= min_weight)
+                .for_each(|l| {
+                    let _ = g.add_link(l);
+                });
+            g
+        }
+    }
+
+    // Graph layouts, node id => (x, y)
+    impl Graph {
+        // places the nodes evenly on the unit circle in `nodes` order,
+        // starting from (1, 0) and going counter clockwise
+        pub fn circular_layout(&self) -> HashMap<String, (f64, f64)> {
+            let step = 2.0 * std::f64::consts::PI / self.nodes.len() as f64;
+            self.nodes
+                .iter()
+                .enumerate()
+                .map(|(i, n)| {
+                    let angle = step * i as f64;
+                    (n.id.clone(), (angle.cos(), angle.sin()))
+                })
+                .collect()
+        }
+
+        // fruchterman-reingold spring embedder over the undirected
+        // interpretation of the graph. nodes start at seeded random positions
+        // in the unit square, linked nodes attract and all pairs repel while
+        // the step size cools down linearly over the iterations
+        pub fn force_layout(&self, iterations: usize, seed: u64) -> HashMap<String, (f64, f64)> {
+            let n = self.nodes.len();
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut pos: Vec<(f64, f64)> = (0..n).map(|_| (rng.gen(), rng.gen())).collect();
+            // ideal distance between nodes
+            let k = (1.0 / n.max(1) as f64).sqrt();
+            let edges: Vec<(usize, usize)> = self
+                .links
+                .values()
+                .map(|l| (self.nodes_map[&l.source], self.nodes_map[&l.target]))
+                .filter(|(s, t)| s != t)
+                .collect();
+            for iter in 0..iterations {
+                let temperature = 0.1 * (1.0 - iter as f64 / iterations as f64);
+                let mut disp = vec![(0.0, 0.0); n];
+                for i in 0..n {
+                    for j in (i + 1)..n {
+                        let (dx, dy) = (pos[i].0 - pos[j].0, pos[i].1 - pos[j].1);
+                        let d = (dx * dx + dy * dy).sqrt().max(1e-9);
+                        let force = k * k / d;
+                        disp[i].0 += dx / d * force;
+                        disp[i].1 += dy / d * force;
+                        disp[j].0 -= dx / d * force;
+                        disp[j].1 -= dy / d * force;
+                    }
+                }
+                edges.iter().for_each(|&(s, t)| {
+                    let (dx, dy) = (pos[s].0 - pos[t].0, pos[s].1 - pos[t].1);
+                    let d = (dx * dx + dy * dy).sqrt().max(1e-9);
+                    let force = d * d / k;
+                    disp[s].0 -= dx / d * force;
+                    disp[s].1 -= dy / d * force;
+                    disp[t].0 += dx / d * force;
+                    disp[t].1 += dy / d * force;
+                });
+                pos.iter_mut().zip(disp.iter()).for_each(|(p, &(dx, dy))| {
+                    let d = (dx * dx + dy * dy).sqrt().max(1e-9);
+                    p.0 += dx / d * d.min(temperature);
+                    p.1 += dy / d * d.min(temperature);
+                });
+            }
+            self.nodes
+                .iter()
+                .zip(pos)
+                .map(|(n, p)| (n.id.clone(), p))
+                .collect()
+        }
+    }
+
+    // how many nodes and neighbors per node `Display` prints before truncating
+    const DISPLAY_NODE_LIMIT: usize = 20;
+    const DISPLAY_NEIGHBOR_LIMIT: usize = 10;
+
+    impl fmt::Display for Graph {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            writeln!(
+                f,
+                "{} {} graph: {} nodes, {} links",
+                if self.directed {
+                    "directed"
+                } else {
+                    "undirected"
+                },
+                if self.weighted {
+                    "weighted"
+                } else {
+                    "unweighted"
+                },
+                self.nodes.len(),
+                self.links.len()
+            )?;
+            let mut out: HashMap<&str, Vec<&str>> = HashMap::new();
+            self.links.values().for_each(|l| {
+                out.entry(&l.source).or_default().push(&l.target);
+                if !self.directed && l.source != l.target {
+                    out.entry(&l.target).or_default().push(&l.source);
+                }
+            });
+            for n in self.nodes.iter().take(DISPLAY_NODE_LIMIT) {
+                let mut neighbors = out.remove(n.id.as_str()).unwrap_or_default();
+                neighbors.sort_unstable();
+                let mut listing = neighbors
+                    .iter()
+                    .take(DISPLAY_NEIGHBOR_LIMIT)
+                    .cloned()
+                    .collect::<Vec<&str>>()
+                    .join(", ");
+                if neighbors.len() > DISPLAY_NEIGHBOR_LIMIT {
+                    listing.push_str(&format!(
+                        ", ... ({} more)",
+                        neighbors.len() - DISPLAY_NEIGHBOR_LIMIT
+                    ));
+                }
+                writeln!(f, "  {} -> [{}]", n.id, listing)?;
+            }
+            if self.nodes.len() > DISPLAY_NODE_LIMIT {
+                writeln!(
+                    f,
+                    "  ... ({} more nodes)",
+                    self.nodes.len() - DISPLAY_NODE_LIMIT
+                )?;
+            }
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        // Note this useful idiom: importing names from outer (for mod tests) scope.
+        use super::*;
+
+        // undirected graph with a weight 1.0 link per pair, nodes are
+        // created by the links
+        fn help_create_undirected_graph(links: &[(&str, &str)]) -> Graph {
+            let mut g = Graph::new();
+            g.directed = false;
+            links.iter().for_each(|(s, t)| {
+                g.add_link(&Link::new(
+                    s.to_string(),
+                    t.to_string(),
+                    "".to_string(),
+                    1.0,
+                ))
+                .unwrap();
+            });
+            g
+        }
+
+        // a -> b and b -> a whose `directed` field was then switched off, so
+        // both links are still there under their directed keys
+        fn help_create_flipped_graph() -> Graph {
+            let mut g = Graph::new();
+            for (s, t) in &[("a", "b"), ("b", "a")] {
+                g.add_link(&Link::new(
+                    s.to_string(),
+                    t.to_string(),
+                    "".to_string(),
+                    1.0,
+                ))
+                .unwrap();
+            }
+            g.directed = false;
+            g
+        }
+
+        fn help_create_test_directed_graph() -> Graph {
+            let mut g = Graph::new();
+            let n1 = Node::new("a".to_string(), "a".to_string());
+            let n2 = Node::new("b".to_string(), "b".to_string());
+            let n3 = Node::new("c".to_string(), "c".to_string());
+            let n4 = Node::new("d".to_string(), "d".to_string());
+            g.add_node(&n1).unwrap();
+            g.add_node(&n2).unwrap();
+            g.add_node(&n3).unwrap();
+            g.add_node(&n4).unwrap();
+            g.add_link(&Link {
+                source: "a".to_string(),
+                target: "b".to_string(),
+                label: "".to_string(),
+                weight: 1.0,
+                ..Default::default()
+            })
+            .unwrap();
+            g.add_link(&Link {
+                source: "c".to_string(),
+                target: "d".to_string(),
+                label: "".to_string(),
+                weight: 1.0,
+                ..Default::default()
+            })
+            .unwrap();
+            g
+        }
+
+        #[test]
+        fn test_create_graph() {
+            let g = Graph::new();
+            assert!(!g.weighted);
+            assert!(g.directed);
+            assert_eq!(g.nodes.len(), 0);
+            assert_eq!(g.nodes_map.len(), 0);
+            assert_eq!(g.links.len(), 0);
+        }
+
+        #[test]
+        fn test_new_node() {
+            let n1 = Node::new("1".to_string(), "1".to_string());
+            let n2 = Node {
+                id: "1".to_string(),
                 name: "1".to_string(),
+                ..Default::default()
+            };
+            assert_eq!(n1, n2);
+        }
+
+        #[test]
+        fn test_clone_node() {
+            let n1 = Node::new("1".to_string(), "1".to_string());
+            let n2 = n1.clone();
+            assert_eq!(n1, n2);
+        }
+
+        #[test]
+        fn test_clone_link() {
+            let l1 = Link {
+                source: "1".to_string(),
+                target: "2".to_string(),
+                label: "1".to_string(),
+                weight: 1.0,
+                ..Default::default()
+            };
+            let l2 = l1.clone();
+            assert_eq!(l1.source, l2.source);
+            assert_eq!(l1.target, l2.target);
+            assert_eq!(l1.label, l2.label);
+            assert_eq!(l1.weight, l2.weight);
+        }
+
+        #[test]
+        fn test_add_node() {
+            let mut g = Graph::new();
+            let n1 = Node::new("1".to_string(), "1".to_string());
+            g.add_node(&n1).unwrap();
+            assert_eq!(g.get_node(&n1.id).unwrap(), n1);
+            // same nodes will not duplicate
+            assert!(g.add_node(&n1).is_err());
+            assert_eq!(g.nodes.len(), 1);
+            assert_eq!(g.nodes_map.len(), 1);
+        }
+
+        #[test]
+        fn test_add_link() {
+            let mut g = Graph::new();
+            let l1 = Link {
+                source: "1".to_string(),
+                target: "2".to_string(),
+                label: "1".to_string(),
+                weight: 1.0,
+                ..Default::default()
+            };
+            g.add_link(&l1).unwrap();
+            assert_eq!(g.nodes.len(), 2);
+            assert_eq!(g.nodes_map.len(), 2);
+            assert_eq!(g.links.len(), 1);
+            assert_eq!(g.get_node("1").unwrap().id, "1");
+            assert_eq!(g.get_node("2").unwrap().id, "2");
+            assert_eq!(g.get_link("1", "2").unwrap().source, "1");
+            assert_eq!(g.get_link("1", "2").unwrap().target, "2");
+        }
+
+        #[test]
+        fn test_to_matrix() {
+            let mut g = Graph::new();
+            let l1 = Link {
+                source: "1".to_string(),
+                target: "2".to_string(),
+                label: "1".to_string(),
+                weight: 1.0,
+                ..Default::default()
+            };
+            g.add_link(&l1).unwrap();
+            assert_eq!(g.to_matrix(), vec![vec![false, true], vec![false, false]]);
+            g.directed = false;
+            assert_eq!(g.to_matrix(), vec![vec![false, true], vec![true, false]]);
+        }
+
+        #[test]
+        fn test_direct_connected() {
+            let mut g = Graph::new();
+            g.add_link(&Link {
+                source: "1".to_string(),
+                target: "2".to_string(),
+                label: "1".to_string(),
+                weight: 1.0,
+                ..Default::default()
+            })
+            .unwrap();
+            g.add_link(&Link {
+                source: "1".to_string(),
+                target: "3".to_string(),
+                label: "1".to_string(),
+                weight: 1.0,
+                ..Default::default()
+            })
+            .unwrap();
+            assert_eq!(
+                g.direct_connected("1"),
+                vec![
+                    Node {
+                        id: "2".to_string(),
+                        name: "".to_string(),
+                        ..Default::default()
+                    },
+                    Node {
+                        id: "3".to_string(),
+                        name: "".to_string(),
+                        ..Default::default()
+                    }
+                ]
+            );
+            assert_eq!(g.direct_connected("2"), vec![]);
+            g.directed = false;
+            assert_eq!(
+                g.direct_connected("2"),
+                vec![Node {
+                    id: "1".to_string(),
+                    name: "".to_string(),
+                    ..Default::default()
+                },]
+            );
+        }
+
+        #[test]
+        fn test_connected_components_ok() {
+            let mut g = Graph::new();
+            g.directed = false;
+            assert_eq!(g.connected_components().len(), 0);
+            assert!(!g.directed);
+            let n1 = Node::new("a".to_string(), "a".to_string());
+            let n2 = Node::new("b".to_string(), "b".to_string());
+            let n3 = Node::new("c".to_string(), "c".to_string());
+            let n4 = Node::new("d".to_string(), "d".to_string());
+            g.add_node(&n1).unwrap();
+            g.add_node(&n2).unwrap();
+            g.add_node(&n3).unwrap();
+            g.add_node(&n4).unwrap();
+            g.add_link(&Link {
+                source: "a".to_string(),
+                target: "b".to_string(),
+                label: "".to_string(),
+                weight: 1.0,
+                ..Default::default()
+            })
+            .unwrap();
+            g.add_link(&Link {
+                source: "c".to_string(),
+                target: "d".to_string(),
+                label: "".to_string(),
+                weight: 1.0,
+                ..Default::default()
+            })
+            .unwrap();
+            g.directed = true;
+            assert_eq!(g.connected_components().len(), 2);
+            assert!(g.directed);
+        }
+
+        #[test]
+        fn test_indegree() {
+            let g = help_create_test_directed_graph();
+            assert_eq!(g.indegree("a"), 0);
+            assert_eq!(g.indegree("b"), 1);
+        }
+
+        #[test]
+        fn test_outdegree() {
+            let g = help_create_test_directed_graph();
+            assert_eq!(g.outdegree("a"), 1);
+            assert_eq!(g.outdegree("b"), 0);
+        }
+
+        #[test]
+        fn test_degree_centrality() {
+            let g = help_create_test_directed_graph();
+            assert_eq!(g.degree_centrality("a"), 1);
+            assert_eq!(g.degree_centrality("b"), 1);
+        }
+
+        #[test]
+        fn test_pagerank_centrality() {
+            let g = help_create_test_directed_graph();
+            assert_eq!(g.pagerank_centrality("a"), 0.0);
+        }
+
+        #[test]
+        fn test_dot_round_trip() {
+            let mut g = help_create_test_directed_graph();
+            g.add_link(&Link {
+                source: "b".to_string(),
+                target: "c".to_string(),
+                label: "b to \"c\"".to_string(),
+                weight: 2.5,
+                ..Default::default()
+            })
+            .unwrap();
+            let parsed = Graph::from_dot(&g.to_dot()).unwrap();
+            assert_eq!(parsed.directed, g.directed);
+            assert_eq!(parsed.nodes, g.nodes);
+            assert_eq!(parsed.links.len(), g.links.len());
+            g.links.values().for_each(|l| {
+                let p = parsed.get_link(&l.source, &l.target).unwrap();
+                assert_eq!(p.label, l.label);
+                assert_eq!(p.weight, l.weight);
+            });
+        }
+
+        #[test]
+        fn test_from_dot() {
+            let g = Graph::from_dot(
+                "graph G {\n  // a comment\n  x [label=\"X\"];\n  x -- y -- z [weight=3];\n}",
+            )
+            .unwrap();
+            assert!(!g.directed);
+            assert_eq!(g.nodes.len(), 3);
+            assert_eq!(g.get_node("x").unwrap().name, "X");
+            assert_eq!(g.get_link("y", "z").unwrap().weight, 3.0);
+            let err = Graph::from_dot("digraph {\n  a -- b;\n}").unwrap_err();
+            assert!(err.contains("line 2, column 5"));
+            assert!(Graph::from_dot("digraph { a -> }").is_err());
+            let err = Graph::from_dot("digraph {\n  a -> b [weight=x];\n}").unwrap_err();
+            assert!(err.contains("invalid weight x at line 2, column 18"));
+        }
+
+        #[test]
+        fn test_add_edges_from_reader() {
+            let data = "# source target weight\na b 1.5\nb,c,2\n\na b 3\nc d\n";
+            let mut g = Graph::new();
+            let added = g
+                .add_edges_from_reader(data.as_bytes(), false, true)
+                .unwrap();
+            assert_eq!(added, 3);
+            assert!(!g.directed);
+            assert!(g.weighted);
+            assert_eq!(g.nodes.len(), 4);
+            assert_eq!(g.get_link("a", "b").unwrap().weight, 1.5);
+            assert_eq!(g.get_link("c", "d").unwrap().weight, 1.0);
+            assert!(g
+                .add_edges_from_reader("a b x".as_bytes(), false, true)
+                .is_err());
+        }
+
+        #[test]
+        fn test_add_edges_from_reader_into_undirected_graph() {
+            let mut g = Graph::new();
+            g.directed = false;
+            g.weighted = true;
+            g.add_link(&Link::new(
+                "b".to_string(),
+                "a".to_string(),
+                "".to_string(),
+                1.0,
+            ))
+            .unwrap();
+            // a b is the existing b a link, the flags of the graph are kept
+            let added = g
+                .add_edges_from_reader("a b 2\nc b 3\n".as_bytes(), false, true)
+                .unwrap();
+            assert_eq!(added, 1);
+            assert_eq!(g.links.len(), 2);
+            assert_eq!(g.get_link("a", "b").unwrap().weight, 1.0);
+            assert_eq!(g.get_link("b", "c").unwrap().weight, 3.0);
+            assert!(g.remove_link("a", "b").is_some());
+
+            let before = g.clone();
+            assert!(g
+                .add_edges_from_reader("d e\n".as_bytes(), true, true)
+                .is_err());
+            assert_eq!(g, before);
+        }
+
+        #[test]
+        fn test_duplicate_error() {
+            let mut g = help_create_test_directed_graph();
+            let err = g.add_node(&Node::new("a".to_string(), "a".to_string()));
+            assert_eq!(err, Err(GraphError::NodeExists("a".to_string())));
+            let err = g
+                .add_link(&Link {
+                    source: "a".to_string(),
+                    target: "b".to_string(),
+                    label: "".to_string(),
+                    weight: 1.0,
+                    ..Default::default()
+                })
+                .unwrap_err();
+            assert_eq!(
+                err,
+                GraphError::LinkExists("a".to_string(), "b".to_string())
+            );
+            assert_eq!(
+                err.to_string(),
+                "[WARN] link a to b is already existed, skipping"
+            );
+        }
+
+        #[cfg(feature = "logging")]
+        #[test]
+        fn test_duplicate_logs_warning() {
+            use std::sync::Mutex;
+
+            struct TestLogger(Mutex<Vec<String>>);
+            impl log::Log for TestLogger {
+                fn enabled(&self, _: &log::Metadata) -> bool {
+                    true
+                }
+                fn log(&self, record: &log::Record) {
+                    if record.level() == log::Level::Warn {
+                        self.0.lock().unwrap().push(record.args().to_string());
+                    }
+                }
+                fn flush(&self) {}
+            }
+            static LOGGER: TestLogger = TestLogger(Mutex::new(Vec::new()));
+            log::set_logger(&LOGGER).unwrap();
+            log::set_max_level(log::LevelFilter::Warn);
+
+            let mut g = help_create_test_directed_graph();
+            assert!(g
+                .add_node(&Node::new("a".to_string(), "".to_string()))
+                .is_err());
+            assert!(LOGGER
+                .0
+                .lock()
+                .unwrap()
+                .contains(&"[WARN] nodea is already existed, skipping".to_string()));
+        }
+
+        #[test]
+        fn test_node_attributes() {
+            let mut n = Node::new("a".to_string(), "a".to_string());
+            assert_eq!(n.get_attr("x"), None);
+            assert_eq!(n.set_attr("x", "1.5"), None);
+            assert_eq!(n.set_attr("x", "2.5"), Some("1.5".to_string()));
+            assert_eq!(n.get_attr("x"), Some(&"2.5".to_string()));
+            let mut g = Graph::new();
+            g.add_node(&n).unwrap();
+            assert_eq!(g.get_node("a").unwrap().get_attr("x").unwrap(), "2.5");
+            // attributes survive a DOT round trip
+            let parsed = Graph::from_dot(&g.to_dot()).unwrap();
+            assert_eq!(parsed.get_node("a").unwrap().attributes, n.attributes);
+        }
+
+        #[test]
+        fn test_link_attributes() {
+            let mut l = Link::new("a".to_string(), "b".to_string(), "".to_string(), 1.0);
+            assert_eq!(l.get_attr("type"), None);
+            l.set_attr("type", "road");
+            let mut g = Graph::new();
+            g.add_link(&l).unwrap();
+            assert_eq!(
+                g.get_link("a", "b").unwrap().get_attr("type").unwrap(),
+                "road"
+            );
+            let parsed = Graph::from_dot(&g.to_dot()).unwrap();
+            assert_eq!(parsed.get_link("a", "b").unwrap().attributes, l.attributes);
+        }
+
+        #[test]
+        fn test_display() {
+            let g = help_create_test_directed_graph();
+            let s = g.to_string();
+            assert!(s.starts_with("directed unweighted graph: 4 nodes, 2 links\n"));
+            assert!(s.contains("  a -> [b]\n"));
+            assert!(s.contains("  b -> []\n"));
+            let mut g = Graph::new();
+            (0..30).for_each(|i| {
+                g.add_link(&Link::new(
+                    "hub".to_string(),
+                    i.to_string(),
+                    "".to_string(),
+                    1.0,
+                ))
+                .unwrap();
+            });
+            let s = g.to_string();
+            assert!(s.contains("31 nodes, 30 links"));
+            assert!(s.contains(", ... (20 more)]"));
+            assert!(s.ends_with("  ... (11 more nodes)\n"));
+        }
+
+        #[test]
+        fn test_node_index() {
+            let g = help_create_test_directed_graph();
+            let m = g.to_matrix();
+            let (a, b) = (g.node_index("a").unwrap(), g.node_index("b").unwrap());
+            assert_eq!(g.nodes[a].id, "a");
+            assert!(m[a][b]);
+            assert!(!m[b].iter().any(|&c| c));
+            assert_eq!(g.node_index("x"), None);
+        }
+
+        #[test]
+        fn test_undirected_link_key() {
+            let mut g = Graph::new();
+            g.directed = false;
+            let l = Link::new("b".to_string(), "a".to_string(), "ba".to_string(), 1.0);
+            g.add_link(&l).unwrap();
+            assert!(g
+                .add_link(&Link::new(
+                    "a".to_string(),
+                    "b".to_string(),
+                    "".to_string(),
+                    1.0
+                ))
+                .is_err());
+            assert_eq!(g.links.len(), 1);
+            assert_eq!(g.get_link("a", "b").unwrap().label, "ba");
+            assert_eq!(g.get_link("b", "a").unwrap().label, "ba");
+            assert_eq!(g.link_key("b", "a"), g.link_key("a", "b"));
+            g.directed = true;
+            assert_ne!(g.link_key("b", "a"), g.link_key("a", "b"));
+        }
+
+        #[test]
+        fn test_set_directed() {
+            let mut g = Graph::new();
+            g.directed = false;
+            g.add_link(&Link::new(
+                "b".to_string(),
+                "a".to_string(),
+                "ba".to_string(),
+                1.0,
+            ))
+            .unwrap();
+            g.set_directed(true);
+            assert!(g.get_link("b", "a").is_some());
+            assert!(g.get_link("a", "b").is_none());
+
+            g.add_link(&Link::new(
+                "a".to_string(),
+                "b".to_string(),
+                "ab".to_string(),
+                1.0,
+            ))
+            .unwrap();
+            g.set_directed(false);
+            assert_eq!(g.links.len(), 1);
+            assert_eq!(g.get_link("b", "a").unwrap().label, "ab");
+            assert_eq!(g.indegree("b"), 1);
+        }
+
+        #[test]
+        fn test_circular_layout() {
+            let g = help_create_test_directed_graph();
+            let layout = g.circular_layout();
+            assert_eq!(layout.len(), 4);
+            let points: Vec<(f64, f64)> = g.nodes.iter().map(|n| layout[&n.id]).collect();
+            for (i, &(x, y)) in points.iter().enumerate() {
+                assert!(((x * x + y * y).sqrt() - 1.0).abs() < 1e-9);
+                let (nx, ny) = points[(i + 1) % points.len()];
+                let gap = ((nx - x).powi(2) + (ny - y).powi(2)).sqrt();
+                assert!((gap - 2f64.sqrt()).abs() < 1e-9);
+            }
+            assert!(Graph::new().circular_layout().is_empty());
+        }
+
+        #[test]
+        fn test_force_layout() {
+            let mut g = Graph::new();
+            g.directed = false;
+            for (s, t) in &[
+                ("a", "b"),
+                ("b", "c"),
+                ("c", "a"),
+                ("x", "y"),
+                ("y", "z"),
+                ("z", "x"),
+            ] {
+                g.add_link(&Link::new(
+                    s.to_string(),
+                    t.to_string(),
+                    "".to_string(),
+                    1.0,
+                ))
+                .unwrap();
+            }
+            let layout = g.force_layout(200, 42);
+            assert_eq!(layout, g.force_layout(200, 42));
+            let dist = |a: &str, b: &str| {
+                let ((ax, ay), (bx, by)) = (layout[a], layout[b]);
+                ((ax - bx).powi(2) + (ay - by).powi(2)).sqrt()
             };
-            assert_eq!(n1, n2);
+            for (s, t) in &[("a", "b"), ("b", "c"), ("x", "y"), ("y", "z")] {
+                assert!(dist(s, t) < dist("a", "x"));
+                assert!(dist(s, t) < dist("c", "z"));
+            }
+        }
+
+        #[test]
+        fn test_strength() {
+            let mut g = help_create_test_directed_graph();
+            g.add_link(&Link::new(
+                "c".to_string(),
+                "a".to_string(),
+                "".to_string(),
+                3.0,
+            ))
+            .unwrap();
+            g.links.get_mut("a_b").unwrap().weight = 2.0;
+            assert_eq!(g.strength("a"), 2.0);
+            g.weighted = true;
+            assert_eq!(g.strength("a"), 5.0);
+            assert_eq!(g.strength("b"), 2.0);
+            assert_eq!(g.strength("x"), 0.0);
+        }
+
+        #[test]
+        fn test_path_cost() {
+            let mut g = Graph::new();
+            g.weighted = true;
+            for (s, t, w) in &[("a", "b", 1.5), ("b", "c", 2.0), ("a", "c", 4.0)] {
+                g.add_link(&Link::new(s.to_string(), t.to_string(), "".to_string(), *w))
+                    .unwrap();
+            }
+            let path = crate::algo::algo::dijkstra_shortest(&g, "a", "c");
+            assert_eq!(g.path_cost(&path), Some(1.5 + 2.0));
+            assert!(g.is_valid_path(&path));
+            g.weighted = false;
+            assert_eq!(g.path_cost(&path), Some(2.0));
+            let backwards: Vec<Node> = path.into_iter().rev().collect();
+            assert_eq!(g.path_cost(&backwards), None);
+            assert!(!g.is_valid_path(&backwards));
+            assert!(!g.is_valid_path(&[]));
+        }
+
+        #[test]
+        fn test_largest_component_size() {
+            let mut g = Graph::new();
+            assert_eq!(g.largest_component_size(), 0);
+            g.add_link(&Link::new(
+                "a".to_string(),
+                "b".to_string(),
+                "".to_string(),
+                1.0,
+            ))
+            .unwrap();
+            g.add_link(&Link::new(
+                "c".to_string(),
+                "b".to_string(),
+                "".to_string(),
+                1.0,
+            ))
+            .unwrap();
+            g.add_node(&Node::new("d".to_string(), "".to_string()))
+                .unwrap();
+            assert_eq!(g.largest_component_size(), 3);
+            assert_eq!(g.connected_components().len(), 2);
+            assert!(g.directed);
+        }
+
+        #[test]
+        fn test_induced_subgraph() {
+            let g = help_create_test_directed_graph();
+            let sub = g.induced_subgraph(&["a".to_string(), "b".to_string(), "c".to_string()]);
+            assert_eq!(sub.nodes.len(), 3);
+            assert_eq!(sub.links.len(), 1);
+            assert!(sub.get_link("a", "b").is_some());
+            assert_eq!(sub.directed, g.directed);
+        }
+
+        #[test]
+        fn test_induced_subgraph_flipped() {
+            let g = help_create_flipped_graph();
+            let sub = g.induced_subgraph(&["a".to_string(), "b".to_string()]);
+            assert_eq!(sub.nodes.len(), 2);
+            assert_eq!(sub.links.len(), 1);
+            assert!(sub.edge_exists("a", "b"));
+        }
+
+        #[test]
+        fn test_rich_club_coefficient() {
+            let mut g = Graph::new();
+            g.directed = false;
+            // hubs h1..h3 form a triangle and each has two leaves
+            let mut links = vec![("h1", "h2"), ("h2", "h3"), ("h1", "h3")];
+            links.extend(&[("h1", "l1"), ("h1", "l2"), ("h2", "l3"), ("h2", "l4")]);
+            links.extend(&[("h3", "l5"), ("h3", "l6")]);
+            links.iter().for_each(|(s, t)| {
+                g.add_link(&Link::new(
+                    s.to_string(),
+                    t.to_string(),
+                    "".to_string(),
+                    1.0,
+                ))
+                .unwrap();
+            });
+            assert_eq!(g.rich_club_coefficient(1), 1.0);
+            // every node has degree > 0, 9 of 36 possible links exist
+            assert_eq!(g.rich_club_coefficient(0), 9.0 / 36.0);
+            assert_eq!(g.rich_club_coefficient(4), 0.0);
+        }
+
+        #[test]
+        fn test_concurrent_degree_queries() {
+            let mut g = help_create_test_directed_graph();
+            g.add_link(&Link::new(
+                "c".to_string(),
+                "b".to_string(),
+                "".to_string(),
+                1.0,
+            ))
+            .unwrap();
+            let shared = &g;
+            let degrees: Vec<(String, usize)> = shared
+                .nodes
+                .par_iter()
+                .map(|n| (n.id.clone(), shared.indegree(&n.id)))
+                .collect();
+            assert_eq!(
+                degrees,
+                vec![
+                    ("a".to_string(), 0),
+                    ("b".to_string(), 2),
+                    ("c".to_string(), 0),
+                    ("d".to_string(), 1)
+                ]
+            );
+            let sizes: Vec<usize> = (0..4)
+                .into_par_iter()
+                .map(|_| shared.largest_component_size())
+                .collect();
+            assert_eq!(sizes, vec![4; 4]);
+        }
+
+        #[test]
+        fn test_to_bitset_matrix() {
+            let mut g = Graph::new();
+            // enough nodes to span more than one word per row
+            (0..70).for_each(|i| {
+                let l = Link::new(
+                    i.to_string(),
+                    ((i * 7) % 70).to_string(),
+                    "".to_string(),
+                    1.0,
+                );
+                let _ = g.add_link(&l);
+            });
+            for directed in &[true, false] {
+                g.set_directed(*directed);
+                let (bits, bools) = (g.to_bitset_matrix(), g.to_matrix());
+                assert_eq!(bits[0].len(), 2);
+                for (bit_row, bool_row) in bits.iter().zip(bools.iter()) {
+                    for (j, &cell) in bool_row.iter().enumerate() {
+                        assert_eq!(bitset_contains(bit_row, j), cell);
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn test_to_csr() {
+            let mut g = help_create_test_directed_graph();
+            g.add_link(&Link::new(
+                "a".to_string(),
+                "d".to_string(),
+                "".to_string(),
+                2.5,
+            ))
+            .unwrap();
+            g.weighted = true;
+            let (row_ptr, cols, values) = g.to_csr();
+            assert_eq!(row_ptr, vec![0, 2, 2, 3, 3]);
+            assert_eq!(cols, vec![1, 3, 3]);
+            assert_eq!(values, vec![1.0, 2.5, 1.0]);
+            // look up a -> d from the arrays
+            let (a, d) = (g.node_index("a").unwrap(), g.node_index("d").unwrap());
+            let pos = (row_ptr[a]..row_ptr[a + 1])
+                .find(|&i| cols[i] == d)
+                .unwrap();
+            assert_eq!(values[pos], 2.5);
+            g.set_directed(false);
+            g.weighted = false;
+            let (row_ptr, cols, values) = g.to_csr();
+            assert_eq!(row_ptr, vec![0, 2, 3, 4, 6]);
+            assert_eq!(cols, vec![1, 3, 0, 3, 0, 2]);
+            assert!(values.iter().all(|&v| v == 1.0));
+        }
+
+        #[test]
+        fn test_filter_edges_by_weight() {
+            let mut g = help_create_test_directed_graph();
+            g.links.get_mut("c_d").unwrap().weight = 0.2;
+            g.weighted = true;
+            let filtered = g.filter_edges_by_weight(0.5);
+            assert_eq!(filtered.nodes, g.nodes);
+            assert_eq!(filtered.links.len(), 1);
+            assert!(filtered.get_link("a", "b").is_some());
+            assert!(filtered.get_link("c", "d").is_none());
+            assert!(filtered.weighted && filtered.directed);
+            assert_eq!(g.links.len(), 2);
+
+            let flipped = help_create_flipped_graph().filter_edges_by_weight(0.0);
+            assert_eq!(flipped.links.len(), 1);
+            assert!(flipped.edge_exists("b", "a"));
+        }
+
+        #[test]
+        fn test_links_by_weight() {
+            let mut g = Graph::new();
+            g.weighted = true;
+            for (s, t, w) in &[
+                ("a", "b", 3.0),
+                ("b", "c", 1.0),
+                ("c", "d", 2.0),
+                ("a", "c", 1.0),
+            ] {
+                g.add_link(&Link::new(s.to_string(), t.to_string(), "".to_string(), *w))
+                    .unwrap();
+            }
+            let order: Vec<(&str, &str)> = g
+                .links_by_weight()
+                .iter()
+                .map(|l| (l.source.as_str(), l.target.as_str()))
+                .collect();
+            assert_eq!(order, vec![("a", "c"), ("b", "c"), ("c", "d"), ("a", "b")]);
+        }
+
+        #[test]
+        fn test_add_or_accumulate_link() {
+            let mut g = Graph::new();
+            g.add_or_accumulate_link(&Link::new(
+                "a".to_string(),
+                "b".to_string(),
+                "".to_string(),
+                1.0,
+            ))
+            .unwrap();
+            assert!(!g.weighted);
+            g.add_or_accumulate_link(&Link::new(
+                "a".to_string(),
+                "b".to_string(),
+                "".to_string(),
+                2.0,
+            ))
+            .unwrap();
+            assert!(g.weighted);
+            assert_eq!(g.links.len(), 1);
+            assert_eq!(g.get_link("a", "b").unwrap().weight, 3.0);
+        }
+
+        #[test]
+        fn test_clear() {
+            let mut g = help_create_test_directed_graph();
+            g.set_directed(false);
+            g.weighted = true;
+            g.clear_edges();
+            assert_eq!(g.nodes.len(), 4);
+            assert_eq!(g.links.len(), 0);
+            g.clear();
+            assert_eq!(g.nodes.len(), 0);
+            assert_eq!(g.nodes_map.len(), 0);
+            assert!(!g.directed && g.weighted);
+            g.add_link(&Link::new(
+                "a".to_string(),
+                "b".to_string(),
+                "".to_string(),
+                1.0,
+            ))
+            .unwrap();
+            assert_eq!(g.node_index("b"), Some(1));
+        }
+
+        #[test]
+        fn test_default_graph() {
+            assert_eq!(Graph::default(), Graph::new());
+            #[derive(Default)]
+            struct Holder {
+                graph: Graph,
+            }
+            let mut h = Holder::default();
+            assert_eq!(h.graph, Graph::new());
+            h.graph
+                .add_node(&Node::new("a".to_string(), "".to_string()))
+                .unwrap();
+            let taken = std::mem::take(&mut h.graph);
+            assert_eq!(taken.nodes.len(), 1);
+            assert_eq!(h.graph, Graph::new());
+        }
+
+        #[test]
+        fn test_collect_links() {
+            let links = vec![
+                Link::new("a".to_string(), "b".to_string(), "".to_string(), 1.0),
+                Link::new("b".to_string(), "c".to_string(), "".to_string(), 1.0),
+                Link::new("a".to_string(), "b".to_string(), "dup".to_string(), 2.0),
+            ];
+            let g: Graph = links.into_iter().collect();
+            assert!(g.directed);
+            assert_eq!(g.nodes.len(), 3);
+            assert_eq!(g.links.len(), 2);
+            assert_eq!(g.get_link("a", "b").unwrap().weight, 1.0);
+        }
+
+        #[cfg(feature = "bincode")]
+        #[test]
+        fn test_bincode_round_trip() {
+            let mut g = help_create_test_directed_graph();
+            g.weighted = true;
+            g.nodes[0].set_attr("x", "1");
+            let mut l = Link::new("b".to_string(), "c".to_string(), "bc".to_string(), 2.5);
+            l.set_attr("kind", "road");
+            g.add_link(&l).unwrap();
+            let bytes = g.to_bytes().unwrap();
+            let decoded = Graph::from_bytes(&bytes).unwrap();
+            assert_eq!(decoded, g);
+            assert_eq!(decoded.get_node("a").unwrap().get_attr("x").unwrap(), "1");
+            assert!(Graph::from_bytes(&bytes[..bytes.len() / 2]).is_err());
+
+            let mut broken = g.clone();
+            let l = Link::new("a".to_string(), "z".to_string(), "".to_string(), 1.0);
+            broken.links.insert(broken.link_key("a", "z"), l);
+            assert!(Graph::from_bytes(&broken.to_bytes().unwrap()).is_err());
+        }
+
+        #[test]
+        fn test_random_subgraph() {
+            let g = help_create_test_directed_graph();
+            assert_eq!(g.random_subgraph(1.0, 3), g);
+            let empty = g.random_subgraph(0.0, 3);
+            assert_eq!(empty.nodes.len(), 0);
+            assert_eq!(empty.links.len(), 0);
+            let half = g.random_subgraph(0.5, 3);
+            assert_eq!(half, g.random_subgraph(0.5, 3));
+            assert!(half
+                .links
+                .values()
+                .all(|l| half.nodes_map.contains_key(&l.source)
+                    && half.nodes_map.contains_key(&l.target)));
+        }
+
+        #[test]
+        #[should_panic]
+        fn test_random_subgraph_invalid_fraction() {
+            help_create_test_directed_graph().random_subgraph(1.5, 0);
+        }
+
+        #[test]
+        fn test_reciprocity() {
+            let mut g = help_create_test_directed_graph();
+            assert_eq!(g.reciprocity(), 0.0);
+            // a <-> b is mutual, c -> d and d -> a are one way
+            g.add_link(&Link::new(
+                "b".to_string(),
+                "a".to_string(),
+                "".to_string(),
+                1.0,
+            ))
+            .unwrap();
+            g.add_link(&Link::new(
+                "d".to_string(),
+                "a".to_string(),
+                "".to_string(),
+                1.0,
+            ))
+            .unwrap();
+            assert_eq!(g.reciprocity(), 0.5);
+            assert_eq!(Graph::new().reciprocity(), 0.0);
+        }
+
+        #[test]
+        fn test_self_loops() {
+            let mut g = help_create_test_directed_graph();
+            assert!(!g.has_self_loops());
+            g.add_link(&Link::new(
+                "a".to_string(),
+                "a".to_string(),
+                "loop".to_string(),
+                1.0,
+            ))
+            .unwrap();
+            assert!(g.has_self_loops());
+            let loops = g.self_loops();
+            assert_eq!(loops.len(), 1);
+            assert_eq!(loops[0].label, "loop");
+            assert_eq!(g.remove_self_loops(), 1);
+            assert!(!g.has_self_loops());
+            assert_eq!(g.links.len(), 2);
+            assert_eq!(g.remove_self_loops(), 0);
+        }
+
+        #[test]
+        fn test_link_prediction_scores() {
+            let mut g = Graph::new();
+            g.directed = false;
+            for (s, t) in &[
+                ("a", "c"),
+                ("b", "c"),
+                ("a", "d"),
+                ("b", "d"),
+                ("d", "e"),
+                ("e", "f"),
+            ] {
+                g.add_link(&Link::new(
+                    s.to_string(),
+                    t.to_string(),
+                    "".to_string(),
+                    1.0,
+                ))
+                .unwrap();
+            }
+            // common neighbors c (degree 2) and d (degree 3)
+            let expected = 1.0 / 2f64.ln() + 1.0 / 3f64.ln();
+            assert!((g.adamic_adar("a", "b") - expected).abs() < 1e-12);
+            // d and f share only e
+            assert!((g.adamic_adar("d", "f") - 1.0 / 2f64.ln()).abs() < 1e-12);
+            assert_eq!(g.adamic_adar("a", "f"), 0.0);
+            assert_eq!(g.preferential_attachment("a", "b"), 4.0);
+            assert_eq!(g.preferential_attachment("d", "f"), 3.0);
+            assert_eq!(g.preferential_attachment("a", "missing"), 0.0);
+            // a self loop is one more neighbor
+            g.add_link(&Link::new(
+                "f".to_string(),
+                "f".to_string(),
+                "".to_string(),
+                1.0,
+            ))
+            .unwrap();
+            assert_eq!(g.preferential_attachment("e", "f"), 4.0);
+        }
+
+        #[test]
+        fn test_snapshot_restore() {
+            let mut g = help_create_test_directed_graph();
+            let before = g.clone();
+            let snapshot = g.snapshot();
+            g.add_link(&Link::new(
+                "d".to_string(),
+                "e".to_string(),
+                "".to_string(),
+                1.0,
+            ))
+            .unwrap();
+            g.remove_self_loops();
+            g.clear_edges();
+            g.weighted = true;
+            g.restore(snapshot);
+            assert_eq!(g, before);
+            assert_eq!(g.node_index("e"), None);
+        }
+
+        #[test]
+        fn test_complement() {
+            let mut g = Graph::new();
+            g.directed = false;
+            for (s, t) in &[("a", "b"), ("b", "c"), ("c", "d")] {
+                g.add_link(&Link::new(
+                    s.to_string(),
+                    t.to_string(),
+                    "".to_string(),
+                    1.0,
+                ))
+                .unwrap();
+            }
+            g.nodes[0].name = "first".to_string();
+            let c = g.complement();
+            assert_eq!(c.nodes, g.nodes);
+            let mut edges: Vec<String> = c.links.keys().cloned().collect();
+            edges.sort();
+            assert_eq!(edges, vec!["a_c", "a_d", "b_d"]);
+            let d = help_create_test_directed_graph().complement();
+            // 4 * 3 ordered pairs minus the two existing links
+            assert_eq!(d.links.len(), 10);
+            assert!(d.get_link("b", "a").is_some());
+            assert!(d.get_link("a", "b").is_none());
+        }
+
+        #[test]
+        fn test_merge_nodes() {
+            let mut g = Graph::new();
+            for (s, t, w) in [
+                ("a", "x", 1.0),
+                ("b", "x", 2.0),
+                ("y", "b", 1.0),
+                ("c", "b", 4.0),
+                ("c", "d", 1.0),
+            ]
+            .iter()
+            {
+                g.add_link(&Link::new(s.to_string(), t.to_string(), "".to_string(), *w))
+                    .unwrap();
+            }
+            // a and b are not adjacent
+            g.merge_nodes("a", "b").unwrap();
+            assert!(g.get_node("b").is_none());
+            assert_eq!(g.nodes.len(), 5);
+            assert_eq!(g.get_link("a", "x").unwrap().weight, 3.0);
+            assert!(!g.weighted);
+            assert!(g.get_link("y", "a").is_some());
+            assert!(g.get_link("c", "a").is_some());
+            assert!(g.get_link("c", "d").is_some());
+            assert_eq!(g.links.len(), 4);
+            assert_eq!(g.node_index("d"), Some(4));
+
+            g.merge_nodes("a", "c").unwrap();
+            assert!(!g.has_self_loops());
+            assert_eq!(
+                g.merge_nodes("a", "z"),
+                Err(GraphError::NodeNotFound("z".to_string()))
+            );
+        }
+
+        #[test]
+        fn test_reserve_and_shrink() {
+            let mut g = help_create_test_directed_graph();
+            g.reserve(100, 200);
+            assert!(g.nodes.capacity() >= 104);
+            assert!(g.nodes_map.capacity() >= 104);
+            assert!(g.in_degrees.capacity() >= 104 && g.out_degrees.capacity() >= 104);
+            assert!(g.links.capacity() >= 202);
+            g.add_link(&Link::new(
+                "d".to_string(),
+                "e".to_string(),
+                "".to_string(),
+                1.0,
+            ))
+            .unwrap();
+            assert_eq!(g.direct_connected("d")[0].id, "e");
+            g.shrink_to_fit();
+            assert!(g.nodes.capacity() < 100);
+            assert!(g.in_degrees.capacity() < 100 && g.out_degrees.capacity() < 100);
+            assert_eq!(g.nodes.len(), 5);
+            assert!(g.get_link("a", "b").is_some());
+        }
+
+        #[test]
+        fn test_normalize_out_weights() {
+            let mut g = Graph::new();
+            for (s, t, w) in [
+                ("a", "b", 1.0),
+                ("a", "c", 3.0),
+                ("b", "c", 2.0),
+                ("c", "a", 5.0),
+            ]
+            .iter()
+            {
+                g.add_link(&Link::new(s.to_string(), t.to_string(), "".to_string(), *w))
+                    .unwrap();
+            }
+            g.add_node(&Node::new("d".to_string(), "".to_string()))
+                .unwrap();
+            g.normalize_out_weights();
+            assert!(g.weighted);
+            for id in ["a", "b", "c"].iter() {
+                let sum: f64 = g
+                    .links
+                    .values()
+                    .filter(|l| l.source == *id)
+                    .map(|l| l.weight)
+                    .sum();
+                assert!((sum - 1.0).abs() < 1e-9);
+            }
+            assert_eq!(g.get_link("a", "c").unwrap().weight, 0.75);
         }
 
         #[test]
-        fn test_clone_node() {
-            let n1 = Node::new("1".to_string(), "1".to_string());
-            let n2 = n1.clone();
-            assert_eq!(n1, n2);
+        fn test_is_isomorphic() {
+            let triangle = help_create_undirected_graph(&[("a", "b"), ("b", "c"), ("c", "a")]);
+            let relabeled = help_create_undirected_graph(&[("z", "x"), ("y", "z"), ("x", "y")]);
+            let path = help_create_undirected_graph(&[("a", "b"), ("b", "c")]);
+            assert!(triangle.is_isomorphic(&relabeled));
+            assert!(!triangle.is_isomorphic(&path));
+
+            // same degree sequence, different structure: a 6-cycle against
+            // two triangles
+            let hexagon = help_create_undirected_graph(&[
+                ("1", "2"),
+                ("2", "3"),
+                ("3", "4"),
+                ("4", "5"),
+                ("5", "6"),
+                ("6", "1"),
+            ]);
+            let two_triangles = help_create_undirected_graph(&[
+                ("1", "2"),
+                ("2", "3"),
+                ("3", "1"),
+                ("4", "5"),
+                ("5", "6"),
+                ("6", "4"),
+            ]);
+            assert!(!hexagon.is_isomorphic(&two_triangles));
+            assert!(hexagon.is_isomorphic(&hexagon.clone()));
         }
 
         #[test]
-        fn test_clone_link() {
-            let l1 = Link {
-                source: "1".to_string(),
-                target: "2".to_string(),
-                label: "1".to_string(),
-                weight: 1.0,
+        fn test_find_subgraph_matches() {
+            // triangles a-b-c and b-c-d share the b-c link, e hangs off d
+            let host = help_create_undirected_graph(&[
+                ("a", "b"),
+                ("b", "c"),
+                ("c", "a"),
+                ("c", "d"),
+                ("d", "b"),
+                ("d", "e"),
+            ]);
+            let triangle = help_create_undirected_graph(&[("x", "y"), ("y", "z"), ("z", "x")]);
+            let matches = host.find_subgraph_matches(&triangle);
+            assert_eq!(matches.len(), 12);
+            let mut triangles: Vec<Vec<String>> = matches
+                .iter()
+                .map(|m| {
+                    let mut ids: Vec<String> = m.values().cloned().collect();
+                    ids.sort();
+                    ids
+                })
+                .collect();
+            triangles.sort();
+            triangles.dedup();
+            assert_eq!(
+                triangles,
+                vec![
+                    vec!["a".to_string(), "b".to_string(), "c".to_string()],
+                    vec!["b".to_string(), "c".to_string(), "d".to_string()],
+                ]
+            );
+
+            // naming a pattern node pins it to nodes of that name
+            let mut named = host.clone();
+            let a = named.node_index("a").unwrap();
+            named.nodes[a].name = "start".to_string();
+            let mut pinned = triangle.clone();
+            let x = pinned.node_index("x").unwrap();
+            pinned.nodes[x].name = "start".to_string();
+            let matches = named.find_subgraph_matches(&pinned);
+            assert_eq!(matches.len(), 2);
+            assert!(matches.iter().all(|m| m["x"] == "a"));
+        }
+
+        #[test]
+        fn test_edge_tuples() {
+            let mut g = help_create_test_directed_graph();
+            g.add_link(&Link::new(
+                "b".to_string(),
+                "c".to_string(),
+                "".to_string(),
+                2.5,
+            ))
+            .unwrap();
+            let tuples: Vec<(String, String, f64)> = g.edge_tuples().collect();
+            assert_eq!(
+                tuples,
+                vec![
+                    ("a".to_string(), "b".to_string(), 1.0),
+                    ("b".to_string(), "c".to_string(), 2.5),
+                    ("c".to_string(), "d".to_string(), 1.0),
+                ]
+            );
+        }
+
+        #[test]
+        fn test_incident_links() {
+            let mut g = help_create_test_directed_graph();
+            g.add_link(&Link::new(
+                "b".to_string(),
+                "c".to_string(),
+                "out".to_string(),
+                2.0,
+            ))
+            .unwrap();
+            g.add_link(&Link::new(
+                "b".to_string(),
+                "b".to_string(),
+                "loop".to_string(),
+                1.0,
+            ))
+            .unwrap();
+            let pairs: Vec<(&str, &str)> = g
+                .incident_links("b")
+                .iter()
+                .map(|l| (l.source.as_str(), l.target.as_str()))
+                .collect();
+            assert_eq!(pairs, vec![("a", "b"), ("b", "b"), ("b", "c")]);
+            assert_eq!(g.incident_links("b")[2].label, "out");
+            assert!(g.incident_links("x").is_empty());
+        }
+
+        #[test]
+        fn test_degree_cache() {
+            let mut g = help_create_test_directed_graph();
+            for (s, t) in [("a", "c"), ("b", "c"), ("c", "a"), ("d", "d"), ("e", "a")].iter() {
+                g.add_link(&Link::new(
+                    s.to_string(),
+                    t.to_string(),
+                    "".to_string(),
+                    1.0,
+                ))
+                .unwrap();
+            }
+            assert!(g.remove_link("b", "c").is_some());
+            assert!(g.remove_link("b", "c").is_none());
+            g.merge_nodes("a", "e").unwrap();
+            g.remove_self_loops();
+            let check = |g: &Graph| {
+                g.nodes.iter().enumerate().for_each(|(i, n)| {
+                    assert_eq!(g.indegree(&n.id), g.indegree_from_matrix(i));
+                    assert_eq!(g.outdegree(&n.id), g.outdegree_from_matrix(i));
+                });
             };
-            let l2 = l1.clone();
-            assert_eq!(l1.source, l2.source);
-            assert_eq!(l1.target, l2.target);
-            assert_eq!(l1.label, l2.label);
-            assert_eq!(l1.weight, l2.weight);
+            check(&g);
+            assert_eq!(g.indegree("c"), 1);
+            assert_eq!(g.outdegree("a"), 2);
+
+            let snapshot = g.snapshot();
+            g.clear_edges();
+            check(&g);
+            g.restore(snapshot);
+            check(&g);
+            assert_eq!(g.outdegree("a"), 2);
         }
 
         #[test]
-        fn test_add_node() {
+        fn test_find_duplicate_names() {
+            let mut g = help_create_test_directed_graph();
+            g.add_node(&Node::new("s1".to_string(), "server".to_string()))
+                .unwrap();
+            g.add_node(&Node::new("s2".to_string(), "server".to_string()))
+                .unwrap();
+            g.add_link(&Link::new(
+                "x".to_string(),
+                "y".to_string(),
+                "".to_string(),
+                1.0,
+            ))
+            .unwrap();
+            let duplicates = g.find_duplicate_names();
+            assert_eq!(duplicates.len(), 1);
+            assert_eq!(
+                duplicates["server"],
+                vec!["s1".to_string(), "s2".to_string()]
+            );
+        }
+
+        #[test]
+        fn test_sample_neighbor() {
             let mut g = Graph::new();
-            let n1 = Node::new("1".to_string(), "1".to_string());
-            g.add_node(&n1);
-            assert_eq!(g.get_node(&n1.id).unwrap(), n1);
-            // same nodes will not duplicate
-            g.add_node(&n1);
-            assert_eq!(g.nodes.len(), 1);
-            assert_eq!(g.nodes_map.len(), 1);
+            g.weighted = true;
+            g.add_link(&Link::new(
+                "a".to_string(),
+                "heavy".to_string(),
+                "".to_string(),
+                9.0,
+            ))
+            .unwrap();
+            g.add_link(&Link::new(
+                "a".to_string(),
+                "light".to_string(),
+                "".to_string(),
+                1.0,
+            ))
+            .unwrap();
+            let heavy = (0..1000)
+                .filter(|&seed| g.sample_neighbor("a", seed).unwrap().id == "heavy")
+                .count();
+            assert!(heavy > 800 && heavy < 980);
+            assert_eq!(g.sample_neighbor("a", 7), g.sample_neighbor("a", 7));
+            assert!(g.sample_neighbor("heavy", 1).is_none());
+            assert!(g.sample_neighbor("x", 1).is_none());
         }
 
         #[test]
-        fn test_add_link() {
+        fn test_burt_constraint() {
+            let star =
+                help_create_undirected_graph(&[("c", "1"), ("c", "2"), ("c", "3"), ("c", "4")]);
+            let clique = help_create_undirected_graph(&[
+                ("a", "b"),
+                ("a", "c"),
+                ("a", "d"),
+                ("b", "c"),
+                ("b", "d"),
+                ("c", "d"),
+            ]);
+            assert!((star.burt_constraint("c") - 0.25).abs() < 1e-9);
+            assert!((clique.burt_constraint("a") - 75.0 / 81.0).abs() < 1e-9);
+            assert!(star.burt_constraint("c") < clique.burt_constraint("a"));
+            assert!((star.burt_constraint("1") - 1.0).abs() < 1e-9);
+            assert_eq!(star.burt_constraint("x"), 0.0);
+        }
+
+        #[test]
+        fn test_component_subgraphs() {
+            let mut g = help_create_test_directed_graph();
+            g.weighted = true;
+            g.add_link(&Link::new(
+                "b".to_string(),
+                "e".to_string(),
+                "".to_string(),
+                2.0,
+            ))
+            .unwrap();
+            let parts = g.component_subgraphs();
+            assert_eq!(parts.len(), 2);
+            assert_eq!(
+                parts.iter().map(|p| p.nodes.len()).sum::<usize>(),
+                g.nodes.len()
+            );
+            assert_eq!(
+                parts.iter().map(|p| p.links.len()).sum::<usize>(),
+                g.links.len()
+            );
+            assert!(parts.iter().all(|p| p.directed && p.weighted));
+            let with_e = parts.iter().find(|p| p.get_node("e").is_some()).unwrap();
+            assert_eq!(with_e.nodes.len(), 3);
+            assert_eq!(with_e.get_link("b", "e").unwrap().weight, 2.0);
+        }
+
+        #[test]
+        fn test_to_matrix_market() {
+            let mut g = help_create_test_directed_graph();
+            g.weighted = true;
+            g.add_link(&Link::new(
+                "d".to_string(),
+                "a".to_string(),
+                "".to_string(),
+                2.5,
+            ))
+            .unwrap();
+            let mm = g.to_matrix_market();
+            let lines: Vec<&str> = mm.lines().collect();
+            assert_eq!(lines[0], "%%MatrixMarket matrix coordinate real general");
+            assert_eq!(lines[1], "4 4 3");
+            assert_eq!(&lines[2..], &["1 2 1", "3 4 1", "4 1 2.5"]);
+        }
+
+        #[test]
+        fn test_edge_exists() {
+            let mut g = help_create_test_directed_graph();
+            assert!(g.edge_exists("a", "b"));
+            assert!(!g.edge_exists("b", "a"));
+            g.set_directed(false);
+            assert!(g.edge_exists("b", "a"));
+            assert!(g.edge_exists("d", "c"));
+            assert!(!g.edge_exists("a", "c"));
+
+            let mut undirected = Graph::new();
+            undirected.set_directed(false);
+            undirected
+                .add_link(&Link::new(
+                    "y".to_string(),
+                    "x".to_string(),
+                    "".to_string(),
+                    1.0,
+                ))
+                .unwrap();
+            assert!(undirected.edge_exists("x", "y"));
+            assert!(undirected.edge_exists("y", "x"));
+        }
+
+        #[test]
+        fn test_weighted_pagerank() {
             let mut g = Graph::new();
-            let l1 = Link {
-                source: "1".to_string(),
-                target: "2".to_string(),
-                label: "1".to_string(),
-                weight: 1.0,
-            };
-            g.add_link(&l1);
-            assert_eq!(g.nodes.len(), 2);
-            assert_eq!(g.nodes_map.len(), 2);
-            assert_eq!(g.links.len(), 1);
-            assert_eq!(g.get_node("1").unwrap().id, "1");
-            assert_eq!(g.get_node("2").unwrap().id, "2");
-            assert_eq!(g.get_link("1", "2").unwrap().source, "1");
-            assert_eq!(g.get_link("1", "2").unwrap().target, "2");
+            g.weighted = true;
+            for (s, t, w) in [
+                ("hub", "heavy", 9.0),
+                ("hub", "light", 1.0),
+                ("heavy", "hub", 1.0),
+                ("light", "hub", 1.0),
+            ]
+            .iter()
+            {
+                g.add_link(&Link::new(s.to_string(), t.to_string(), "".to_string(), *w))
+                    .unwrap();
+            }
+            let rank = g.weighted_pagerank(0.85);
+            assert!(rank["heavy"] > rank["light"] * 2.0);
+            assert!((rank.values().sum::<f64>() - 1.0).abs() < 1e-9);
+            assert!(Graph::new().weighted_pagerank(0.85).is_empty());
         }
 
         #[test]
-        fn test_to_matrix() {
+        fn test_weighted_neighbors() {
             let mut g = Graph::new();
-            let l1 = Link {
-                source: "1".to_string(),
-                target: "2".to_string(),
-                label: "1".to_string(),
-                weight: 1.0,
+            g.weighted = true;
+            for (s, t, w) in [("a", "b", 2.0), ("a", "c", 0.5), ("d", "a", 3.0)].iter() {
+                g.add_link(&Link::new(s.to_string(), t.to_string(), "".to_string(), *w))
+                    .unwrap();
+            }
+            let pairs = |g: &Graph| -> Vec<(String, f64)> {
+                g.weighted_neighbors("a")
+                    .into_iter()
+                    .map(|(n, w)| (n.id, w))
+                    .collect()
             };
-            g.add_link(&l1);
-            assert_eq!(g.to_matrix(), vec![vec![false, true], vec![false, false]]);
-            g.directed = false;
-            assert_eq!(g.to_matrix(), vec![vec![false, true], vec![true, false]]);
+            assert_eq!(
+                pairs(&g),
+                vec![("b".to_string(), 2.0), ("c".to_string(), 0.5)]
+            );
+            g.set_directed(false);
+            assert_eq!(
+                pairs(&g),
+                vec![
+                    ("b".to_string(), 2.0),
+                    ("c".to_string(), 0.5),
+                    ("d".to_string(), 3.0)
+                ]
+            );
+            assert!(g.weighted_neighbors("x").is_empty());
         }
 
         #[test]
-        fn test_direct_connected() {
+        fn test_dot_colors() {
+            let mut g = help_create_test_directed_graph();
+            let plain = g.to_dot();
+            assert!(!plain.contains("color"));
+
+            let a = g.node_index("a").unwrap();
+            g.nodes[a].set_color("red");
+            g.nodes[a].set_attr("group", "x");
+            g.links.get_mut("c_d").unwrap().set_color("#0000ff");
+            let dot = g.to_dot();
+            assert!(dot.contains("\"a\" [label=\"a\", color=\"red\", \"group\"=\"x\"];"));
+            assert!(dot.contains("\"c\" -> \"d\" [label=\"\", weight=1, color=\"#0000ff\"];"));
+
+            let parsed = Graph::from_dot(&dot).unwrap();
+            assert_eq!(parsed.get_node("a").unwrap().color().unwrap(), "red");
+            assert_eq!(
+                parsed.get_link("c", "d").unwrap().color().unwrap(),
+                "#0000ff"
+            );
+        }
+
+        #[test]
+        fn test_degree_stats() {
+            let mut g = help_create_test_directed_graph();
+            assert_eq!(g.degree_stats(), (1, 1.0, 1));
+            g.add_link(&Link::new(
+                "a".to_string(),
+                "c".to_string(),
+                "".to_string(),
+                1.0,
+            ))
+            .unwrap();
+            assert_eq!(g.degree_stats(), (1, 1.5, 2));
+            assert_eq!(Graph::new().degree_stats(), (0, 0.0, 0));
+        }
+
+        #[test]
+        fn test_adjacency_list_round_trip() {
+            let mut g = help_create_test_directed_graph();
+            g.add_node(&Node::new("lonely".to_string(), "".to_string()))
+                .unwrap();
+            g.add_link(&Link::new(
+                "a".to_string(),
+                "c".to_string(),
+                "".to_string(),
+                1.0,
+            ))
+            .unwrap();
+            let adj = g.to_adjacency_list();
+            assert_eq!(adj["a"], vec!["b".to_string(), "c".to_string()]);
+            assert!(adj["lonely"].is_empty());
+            let back = Graph::from_adjacency_list(&adj, true);
+            assert_eq!(back.nodes.len(), g.nodes.len());
+            assert_eq!(back.links.len(), g.links.len());
+            assert!(g
+                .links
+                .values()
+                .all(|l| back.get_link(&l.source, &l.target).is_some()));
+            assert_eq!(back.to_adjacency_list(), adj);
+
+            let mut undirected = g.clone();
+            undirected.set_directed(false);
+            let adj = undirected.to_adjacency_list();
+            assert_eq!(adj["b"], vec!["a".to_string()]);
+            let back = Graph::from_adjacency_list(&adj, false);
+            assert_eq!(back.links.len(), 3);
+            assert_eq!(back.to_adjacency_list(), adj);
+        }
+
+        #[test]
+        fn test_edges_between_sets() {
             let mut g = Graph::new();
-            g.add_link(&Link {
-                source: "1".to_string(),
-                target: "2".to_string(),
-                label: "1".to_string(),
-                weight: 1.0,
-            });
-            g.add_link(&Link {
-                source: "1".to_string(),
-                target: "3".to_string(),
-                label: "1".to_string(),
-                weight: 1.0,
+            let links = [
+                ("a1", "a2"),
+                ("a2", "a3"),
+                ("b1", "b2"),
+                ("a1", "b1"),
+                ("a3", "b2"),
+                ("b2", "a2"),
+            ];
+            links.iter().for_each(|(s, t)| {
+                g.add_link(&Link::new(
+                    s.to_string(),
+                    t.to_string(),
+                    "".to_string(),
+                    1.0,
+                ))
+                .unwrap();
             });
+            let ids = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<String>>();
+            let (a, b) = (ids(&["a1", "a2", "a3"]), ids(&["b1", "b2"]));
+            assert_eq!(g.edges_between_sets(&a, &b), 2);
+            assert_eq!(g.edges_between_sets(&b, &a), 1);
+            g.set_directed(false);
+            assert_eq!(g.edges_between_sets(&a, &b), 3);
+            assert_eq!(g.edges_between_sets(&a, &a), 2);
+        }
+
+        #[test]
+        fn test_all_neighbors() {
+            let mut g = help_create_test_directed_graph();
+            g.add_link(&Link::new(
+                "b".to_string(),
+                "c".to_string(),
+                "".to_string(),
+                1.0,
+            ))
+            .unwrap();
+            let ids = |nodes: Vec<Node>| nodes.into_iter().map(|n| n.id).collect::<Vec<String>>();
+            assert_eq!(ids(g.direct_connected("b")), vec!["c"]);
+            assert_eq!(ids(g.all_neighbors("b")), vec!["a", "c"]);
+            assert_eq!(ids(g.all_neighbors("d")), vec!["c"]);
+            assert!(g.all_neighbors("x").is_empty());
+        }
+
+        #[test]
+        fn test_difference() {
+            let mut g = help_create_test_directed_graph();
+            let older = g.clone();
+            g.add_link(&Link::new(
+                "b".to_string(),
+                "e".to_string(),
+                "".to_string(),
+                1.0,
+            ))
+            .unwrap();
+            g.add_node(&Node::new("f".to_string(), "".to_string()))
+                .unwrap();
+            let added = g.difference(&older);
+            let mut ids: Vec<&str> = added.nodes.iter().map(|n| n.id.as_str()).collect();
+            ids.sort_unstable();
+            // b stays as the source of the new link
+            assert_eq!(ids, vec!["b", "e", "f"]);
+            assert_eq!(added.links.len(), 1);
+            assert!(added.get_link("b", "e").is_some());
+            assert!(older.difference(&g).nodes.is_empty());
+
+            let flipped = help_create_flipped_graph().difference(&Graph::new());
+            assert_eq!(flipped.links.len(), 1);
+            assert!(flipped.edge_exists("a", "b"));
+        }
+
+        #[test]
+        fn test_intersection() {
+            let mut g = help_create_test_directed_graph();
+            g.add_link(&Link::new(
+                "b".to_string(),
+                "c".to_string(),
+                "".to_string(),
+                1.0,
+            ))
+            .unwrap();
+            let mut other = Graph::new();
+            for (s, t, w) in [("a", "b", 7.0), ("b", "c", 1.0), ("c", "x", 1.0)].iter() {
+                other
+                    .add_link(&Link::new(s.to_string(), t.to_string(), "".to_string(), *w))
+                    .unwrap();
+            }
+            let shared = g.intersection(&other);
+            let ids: Vec<&str> = shared.nodes.iter().map(|n| n.id.as_str()).collect();
+            assert_eq!(ids, vec!["a", "b", "c"]);
+            assert_eq!(shared.links.len(), 2);
+            // weights come from self
+            assert_eq!(shared.get_link("a", "b").unwrap().weight, 1.0);
+            assert!(shared.get_link("b", "c").is_some());
+            assert_eq!(shared.get_node("a").unwrap().name, "a");
+        }
+
+        #[test]
+        fn test_validate() {
+            let mut g = help_create_test_directed_graph();
+            assert_eq!(g.validate(), Ok(()));
+            g.nodes_map.insert("a".to_string(), 2);
+            g.nodes_map.insert("ghost".to_string(), 9);
+            let problems = g.validate().unwrap_err();
             assert_eq!(
-                g.direct_connected("1"),
+                problems,
                 vec![
-                    Node {
-                        id: "2".to_string(),
-                        name: "".to_string()
-                    },
-                    Node {
-                        id: "3".to_string(),
-                        name: "".to_string()
-                    }
+                    "[ERROR] node a maps to index 2 which holds node c".to_string(),
+                    "[ERROR] node ghost maps to index 9 out of bounds".to_string(),
                 ]
             );
-            assert_eq!(g.direct_connected("2"), vec![]);
-            g.directed = false;
+
+            let mut g = help_create_test_directed_graph();
+            let l = g.links.remove("a_b").unwrap();
+            g.links.insert("b_a".to_string(), l);
             assert_eq!(
-                g.direct_connected("2"),
-                vec![Node {
-                    id: "1".to_string(),
-                    name: "".to_string()
-                },]
+                g.validate().unwrap_err(),
+                vec!["[ERROR] link a to b is stored under key b_a".to_string()]
             );
         }
 
         #[test]
-        fn test_connected_components_ok() {
+        fn test_weighted_similarity() {
+            let mut g = Graph::new();
+            g.weighted = true;
+            for (s, t, w) in [
+                ("a", "x", 4.0),
+                ("a", "y", 1.0),
+                ("b", "x", 1.0),
+                ("b", "y", 1.0),
+                ("b", "z", 2.0),
+            ]
+            .iter()
+            {
+                g.add_link(&Link::new(s.to_string(), t.to_string(), "".to_string(), *w))
+                    .unwrap();
+            }
+            // shared x and y out of x, y and z
+            assert!((g.jaccard_similarity("a", "b") - 2.0 / 3.0).abs() < 1e-9);
+            // (1 + 1 + 0) / (4 + 1 + 2)
+            assert!((g.weighted_jaccard("a", "b") - 2.0 / 7.0).abs() < 1e-9);
+            // (4 + 1) / (sqrt(17) * sqrt(6))
+            let expected = 5.0 / (17.0f64.sqrt() * 6.0f64.sqrt());
+            assert!((g.cosine_similarity("a", "b") - expected).abs() < 1e-9);
+            assert!((g.cosine_similarity("a", "a") - 1.0).abs() < 1e-9);
+
+            g.weighted = false;
+            assert!((g.weighted_jaccard("a", "b") - g.jaccard_similarity("a", "b")).abs() < 1e-9);
+            assert_eq!(g.weighted_jaccard("x", "y"), 0.0);
+            assert_eq!(g.cosine_similarity("x", "a"), 0.0);
+        }
+
+        #[test]
+        fn test_sources_and_sinks() {
+            let g = help_create_test_directed_graph();
+            let ids = |nodes: Vec<Node>| nodes.into_iter().map(|n| n.id).collect::<Vec<String>>();
+            let (sources, sinks) = g.sources_and_sinks();
+            assert_eq!(ids(sources), vec!["a", "c"]);
+            assert_eq!(ids(sinks), vec!["b", "d"]);
+        }
+
+        #[test]
+        fn test_neighbor_ids() {
+            let mut g = help_create_test_directed_graph();
+            g.add_link(&Link::new(
+                "a".to_string(),
+                "c".to_string(),
+                "".to_string(),
+                1.0,
+            ))
+            .unwrap();
+            g.add_link(&Link::new(
+                "d".to_string(),
+                "a".to_string(),
+                "".to_string(),
+                1.0,
+            ))
+            .unwrap();
+            for directed in [true, false].iter() {
+                g.set_directed(*directed);
+                let mut borrowed: Vec<&str> = g.neighbor_ids("a").collect();
+                borrowed.sort_unstable();
+                let cloned: Vec<String> =
+                    g.direct_connected("a").into_iter().map(|n| n.id).collect();
+                assert_eq!(borrowed, cloned);
+            }
+            assert_eq!(g.neighbor_ids("x").count(), 0);
+        }
+
+        #[test]
+        fn test_line_graph() {
             let mut g = Graph::new();
             g.directed = false;
-            assert_eq!(g.connected_components().len(), 0);
-            assert_eq!(g.directed, false);
-            let n1 = Node::new("a".to_string(), "a".to_string());
-            let n2 = Node::new("b".to_string(), "b".to_string());
-            let n3 = Node::new("c".to_string(), "c".to_string());
-            let n4 = Node::new("d".to_string(), "d".to_string());
-            g.add_node(&n1);
-            g.add_node(&n2);
-            g.add_node(&n3);
-            g.add_node(&n4);
-            g.add_link(&Link {
-                source: "a".to_string(),
-                target: "b".to_string(),
-                label: "".to_string(),
-                weight: 1.0,
-            });
-            g.add_link(&Link {
-                source: "c".to_string(),
-                target: "d".to_string(),
-                label: "".to_string(),
-                weight: 1.0,
+            for (s, t) in [("a", "b"), ("b", "c"), ("c", "d")].iter() {
+                g.add_link(&Link::new(
+                    s.to_string(),
+                    t.to_string(),
+                    "".to_string(),
+                    1.0,
+                ))
+                .unwrap();
+            }
+            let line = g.line_graph();
+            assert!(!line.directed);
+            assert_eq!(line.nodes.len(), 3);
+            assert_eq!(line.links.len(), 2);
+            assert!(line.edge_exists("a_b", "b_c"));
+            assert!(line.edge_exists("b_c", "c_d"));
+            assert!(!line.edge_exists("a_b", "c_d"));
+
+            let mut directed = g.clone();
+            directed.set_directed(true);
+            let line = directed.line_graph();
+            assert_eq!(line.links.len(), 2);
+            assert!(line.get_link("a_b", "b_c").is_some());
+            assert!(line.get_link("b_c", "a_b").is_none());
+        }
+
+        #[test]
+        fn test_core_numbers() {
+            let mut g = Graph::new();
+            g.directed = false;
+            let links = [
+                // k4 core
+                ("a", "b"),
+                ("a", "c"),
+                ("a", "d"),
+                ("b", "c"),
+                ("b", "d"),
+                ("c", "d"),
+                // triangle hanging off a
+                ("a", "t1"),
+                ("t1", "t2"),
+                ("t2", "a"),
+                // chain off d
+                ("d", "p1"),
+                ("p1", "p2"),
+            ];
+            links.iter().for_each(|(s, t)| {
+                g.add_link(&Link::new(
+                    s.to_string(),
+                    t.to_string(),
+                    "".to_string(),
+                    1.0,
+                ))
+                .unwrap();
             });
-            g.directed = true;
-            assert_eq!(g.connected_components().len(), 2);
-            assert_eq!(g.directed, true);
+            g.add_node(&Node::new("alone".to_string(), "".to_string()))
+                .unwrap();
+            let cores = g.core_numbers();
+            for id in ["a", "b", "c", "d"].iter() {
+                assert_eq!(cores[*id], 3);
+            }
+            assert_eq!(cores["t1"], 2);
+            assert_eq!(cores["t2"], 2);
+            assert_eq!(cores["p1"], 1);
+            assert_eq!(cores["p2"], 1);
+            assert_eq!(cores["alone"], 0);
         }
 
         #[test]
-        fn test_indegree() {
-            let mut g = help_create_test_directed_graph();
-            assert_eq!(g.indegree("a"), 0);
-            assert_eq!(g.indegree("b"), 1);
+        fn test_structurally_equal() {
+            let g = help_create_test_directed_graph();
+            let mut relabeled = g.clone();
+            relabeled.links.get_mut("a_b").unwrap().label = "knows".to_string();
+            let c = relabeled.node_index("c").unwrap();
+            relabeled.nodes[c].name = "charlie".to_string();
+            assert_ne!(g, relabeled);
+            assert!(g.structurally_equal(&relabeled));
+
+            let mut reweighted = g.clone();
+            reweighted.links.get_mut("c_d").unwrap().weight = 5.0;
+            assert!(g.structurally_equal(&reweighted));
+            reweighted.weighted = true;
+            assert!(!g.structurally_equal(&reweighted));
+
+            let mut rewired = g.clone();
+            rewired.remove_link("c", "d");
+            rewired
+                .add_link(&Link::new(
+                    "d".to_string(),
+                    "c".to_string(),
+                    "".to_string(),
+                    1.0,
+                ))
+                .unwrap();
+            assert!(!g.structurally_equal(&rewired));
         }
 
         #[test]
-        fn test_outdegree() {
+        fn test_to_csv_tables() {
+            // splits one CSV line, honouring quoted fields
+            fn parse_line(line: &str) -> Vec<String> {
+                let (mut fields, mut field, mut quoted) = (Vec::new(), String::new(), false);
+                let mut chars = line.chars().peekable();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' if quoted && chars.peek() == Some(&'"') => {
+                            field.push('"');
+                            chars.next();
+                        }
+                        '"' => quoted = !quoted,
+                        ',' if !quoted => fields.push(std::mem::take(&mut field)),
+                        _ => field.push(c),
+                    }
+                }
+                fields.push(field);
+                fields
+            }
+
             let mut g = help_create_test_directed_graph();
-            assert_eq!(g.outdegree("a"), 1);
-            assert_eq!(g.outdegree("b"), 0);
+            g.add_node(&Node::new("e".to_string(), "Smith, \"Jo\"".to_string()))
+                .unwrap();
+            g.add_link(&Link::new(
+                "e".to_string(),
+                "a".to_string(),
+                "a, b".to_string(),
+                2.5,
+            ))
+            .unwrap();
+            let (nodes, links) = g.to_csv_tables();
+            let nodes: Vec<Vec<String>> = nodes.lines().map(parse_line).collect();
+            let links: Vec<Vec<String>> = links.lines().map(parse_line).collect();
+            assert_eq!(nodes.len(), 6);
+            assert_eq!(links.len(), 4);
+            assert_eq!(nodes[0], vec!["id", "name"]);
+            assert_eq!(nodes[5], vec!["e", "Smith, \"Jo\""]);
+            assert_eq!(links[0], vec!["source", "target", "label", "weight"]);
+            assert_eq!(links[3], vec!["e", "a", "a, b", "2.5"]);
         }
 
         #[test]
-        fn test_degree_centrality() {
+        fn test_prune_edges() {
+            let mut g = Graph::new();
+            g.weighted = true;
+            for (s, t, w) in [
+                ("a", "b", 0.5),
+                ("b", "c", 2.0),
+                ("c", "a", 1.0),
+                ("c", "d", 0.1),
+            ]
+            .iter()
+            {
+                g.add_link(&Link::new(s.to_string(), t.to_string(), "".to_string(), *w))
+                    .unwrap();
+            }
+            let expected = g.filter_edges_by_weight(1.0);
+            assert_eq!(g.prune_edges(1.0), 2);
+            assert_eq!(g, expected);
+            assert_eq!(g.nodes.len(), 4);
+            assert!(g.get_link("a", "b").is_none());
+            assert_eq!(g.outdegree("c"), 1);
+            assert_eq!(g.validate(), Ok(()));
+        }
+
+        #[test]
+        fn test_enforce_acyclic() {
             let mut g = help_create_test_directed_graph();
-            assert_eq!(g.degree_centrality("a"), 1);
-            assert_eq!(g.degree_centrality("b"), 1);
+            g.add_link(&Link::new(
+                "b".to_string(),
+                "c".to_string(),
+                "".to_string(),
+                1.0,
+            ))
+            .unwrap();
+            g.set_enforce_acyclic(true).unwrap();
+            assert!(g.enforce_acyclic());
+            // d -> a closes a -> b -> c -> d
+            assert_eq!(
+                g.add_link(&Link::new(
+                    "d".to_string(),
+                    "a".to_string(),
+                    "".to_string(),
+                    1.0
+                )),
+                Err(GraphError::CreatesCycle("d".to_string(), "a".to_string()))
+            );
+            assert!(g
+                .add_link(&Link::new(
+                    "a".to_string(),
+                    "a".to_string(),
+                    "".to_string(),
+                    1.0
+                ))
+                .is_err());
+            assert!(g
+                .add_link(&Link::new(
+                    "a".to_string(),
+                    "d".to_string(),
+                    "".to_string(),
+                    1.0
+                ))
+                .is_ok());
+            assert_eq!(g.links.len(), 4);
+            // merging c into a would make a -> b -> a
+            let before = g.clone();
+            assert!(g.merge_nodes("a", "c").is_err());
+            assert_eq!(g, before);
+
+            g.set_enforce_acyclic(false).unwrap();
+            g.add_link(&Link::new(
+                "d".to_string(),
+                "a".to_string(),
+                "".to_string(),
+                1.0,
+            ))
+            .unwrap();
+            assert!(g.set_enforce_acyclic(true).is_err());
+            assert!(!g.enforce_acyclic());
+
+            let mut undirected = Graph::new();
+            undirected.set_directed(false);
+            undirected.set_enforce_acyclic(true).unwrap();
+            undirected
+                .add_link(&Link::new(
+                    "x".to_string(),
+                    "y".to_string(),
+                    "".to_string(),
+                    1.0,
+                ))
+                .unwrap();
+            undirected
+                .add_link(&Link::new(
+                    "y".to_string(),
+                    "z".to_string(),
+                    "".to_string(),
+                    1.0,
+                ))
+                .unwrap();
+            assert!(undirected
+                .add_link(&Link::new(
+                    "z".to_string(),
+                    "x".to_string(),
+                    "".to_string(),
+                    1.0
+                ))
+                .is_err());
         }
 
         #[test]
-        fn test_pagerank_centrality() {
+        fn test_retain_nodes() {
             let mut g = help_create_test_directed_graph();
-            assert_eq!(g.pagerank_centrality("a"), 0.0);
+            // nodes created through add_link have empty names
+            g.add_link(&Link::new(
+                "a".to_string(),
+                "x".to_string(),
+                "".to_string(),
+                1.0,
+            ))
+            .unwrap();
+            g.add_link(&Link::new(
+                "y".to_string(),
+                "d".to_string(),
+                "".to_string(),
+                1.0,
+            ))
+            .unwrap();
+            assert_eq!(g.retain_nodes(|n| !n.name.is_empty()), 2);
+            assert_eq!(g.nodes.len(), 4);
+            assert_eq!(g.links.len(), 2);
+            assert_eq!(g.validate(), Ok(()));
+            assert_eq!(g.outdegree("a"), 1);
+            assert_eq!(g.indegree("d"), 1);
+            assert_eq!(g.node_index("d"), Some(3));
+            assert_eq!(g.retain_nodes(|_| true), 0);
+        }
+
+        #[test]
+        fn test_degree_sequence() {
+            let mut star = Graph::new();
+            star.directed = false;
+            for leaf in ["1", "2", "3", "4", "5"].iter() {
+                star.add_link(&Link::new(
+                    leaf.to_string(),
+                    "hub".to_string(),
+                    "".to_string(),
+                    1.0,
+                ))
+                .unwrap();
+            }
+            assert_eq!(star.degree_sequence(), vec![5, 1, 1, 1, 1, 1]);
+            let g = help_create_test_directed_graph();
+            assert_eq!(g.degree_sequence(), vec![1, 1, 1, 1]);
+            assert!(Graph::new().degree_sequence().is_empty());
         }
     }
 }
@@ -1,2 +1,4 @@
-mod algo;
-mod graph;
+#![allow(clippy::module_inception)]
+pub mod algo;
+pub mod generators;
+pub mod graph;